@@ -1,58 +1,345 @@
-use iced::alignment::Horizontal;
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::canvas::event::{self, Event};
 use iced::widget::canvas::path::arc::Elliptical;
-use iced::widget::canvas::{self, Cache, Canvas, Cursor, Geometry, LineCap, Stroke, Style};
+use iced::widget::canvas::{self, Cache, Canvas, Cursor, Frame, Geometry, LineCap, Path, Stroke, Style};
 use iced::{Element, Theme, Vector};
-use iced::widget::{Column, Row, Text};
 use iced::widget::canvas::path::{Builder};
+use iced_native::mouse::{self, ScrollDelta};
+use iced_native::touch;
 use iced_native::{Color, Length, Point, Rectangle};
+use yaml_rust::Yaml;
 
 use crate::Message;
 
+// The arc leaves a quarter of the circle open at the bottom unless the config says otherwise
+const DEFAULT_SWEEP_DEGREES: f32 = 270.0;
+
+/// Presses closer to the centre than this part of the radius act as the dial button
+const BUTTON_RADIUS: f32 = 0.6;
+
+// Tick lengths, as part of the arc radius
+const MAJOR_TICK_LENGTH: f32 = 0.12;
+const MINOR_TICK_LENGTH: f32 = 0.06;
+
+// Gap between the arc and its labels, as part of the arc radius
+const LABEL_GAP: f32 = 0.2;
+// Rough width of a character of a label, as part of the text size, used to make room for the labels
+const LABEL_CHAR_WIDTH: f32 = 0.6;
+
+/// Where the arc sits on its canvas. Angles are in radians and run clockwise, as the y axis of the screen points down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArcGeometry {
+    /// The angle the arc covers, from the minimum to the maximum of the scale. A full turn makes a circle.
+    pub sweep: f32,
+    /// The direction of the middle of the arc, clockwise from the top
+    pub orientation: f32,
+}
+
+impl Default for ArcGeometry {
+    fn default() -> ArcGeometry {
+        ArcGeometry {
+            sweep: DEFAULT_SWEEP_DEGREES.to_radians(),
+            orientation: 0.0,
+        }
+    }
+}
+
+impl ArcGeometry {
+    pub fn is_full_circle(&self) -> bool {
+        self.sweep >= TAU
+    }
+
+    /// The screen angle the minimum of the scale is drawn at
+    pub fn start(&self) -> f32 {
+        -FRAC_PI_2 + self.orientation - self.sweep / 2.0
+    }
+
+    /// The screen angle of a proportion of the scale, from 0 at the minimum to 1 at the maximum
+    pub fn angle_of(&self, proportion: f32) -> f32 {
+        self.start() + proportion * self.sweep
+    }
+
+    /// The proportion of the scale at a screen angle. Angles in the gap of the arc go to the nearest end.
+    pub fn proportion_at(&self, angle: f32) -> f32 {
+        let relative = (angle - self.start()).rem_euclid(TAU);
+
+        if relative <= self.sweep {
+            relative / self.sweep
+        } else if relative > (self.sweep + TAU) / 2.0 {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    /// The proportion at a screen angle for a pointer dragged from the proportion `previous`. A drag can't cross
+    /// the seam of a full circle, where both ends meet: past it, the drag stays at the end it came from.
+    pub fn dragged_proportion(&self, angle: f32, previous: f32) -> f32 {
+        let proportion = self.proportion_at(angle);
+
+        if self.is_full_circle() && (proportion - previous).abs() > 0.5 {
+            previous.round()
+        } else {
+            proportion
+        }
+    }
+}
+
+/// How the arcs look, set for the whole study in the `arc` section of the config
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArcStyle {
+    pub geometry: ArcGeometry,
+    /// Intervals between major ticks over the whole arc, 0 for none
+    pub major_ticks: u16,
+    /// Intervals between minor ticks within each major interval, 0 or 1 for none
+    pub minor_ticks: u16,
+    /// Marks the anchor the fill starts from, the neutral midpoint of the lie/truth scales
+    pub midpoint_marker: bool,
+    /// A line from the centre to the current value
+    pub needle: bool,
+    /// The current value as a number in the centre
+    pub readout: bool,
+    pub track_colour: Color,
+    /// Fill for values below the anchor, on its left on the default arc
+    pub left_colour: Color,
+    /// Fill for values above the anchor
+    pub right_colour: Color,
+    pub disabled_colour: Color,
+}
+
+impl Default for ArcStyle {
+    fn default() -> ArcStyle {
+        ArcStyle {
+            geometry: ArcGeometry::default(),
+            major_ticks: 0,
+            minor_ticks: 0,
+            midpoint_marker: false,
+            needle: false,
+            readout: false,
+            track_colour: Color::from_rgb(0.8, 0.8, 0.8),
+            left_colour: Color::from_rgb(0.0, 0.0, 0.8),
+            right_colour: Color::from_rgb(0.0, 0.0, 0.8),
+            disabled_colour: Color::from_rgb(0.5, 0.5, 0.8),
+        }
+    }
+}
+
+/// Reads a `#rrggbb` colour
+fn read_colour(yaml: &Yaml, key: &str, default: Color) -> Color {
+    if yaml[key].is_badvalue() {
+        return default;
+    }
+
+    let value = yaml[key].as_str().expect(format!("Could not read the {} arc colour", key).as_str());
+    let hex = value.trim_start_matches('#');
+
+    if hex.len() != 6 {
+        panic!("The {} arc colour '{}' is not in the #rrggbb format", key, value);
+    }
+
+    let channel = |i: usize| -> f32 {
+        u8::from_str_radix(&hex[i..i + 2], 16).expect(format!("The {} arc colour '{}' is not in the #rrggbb format", key, value).as_str()) as f32 / 255.0
+    };
+
+    Color::from_rgb(channel(0), channel(2), channel(4))
+}
+
+impl ArcStyle {
+    /// Reads the `arc` section of the config. Anything left out keeps the plain grey and blue arc.
+    pub fn from_config(yaml_config: &Yaml) -> ArcStyle {
+        let yaml = &yaml_config["arc"];
+        let default = ArcStyle::default();
+
+        if yaml.is_badvalue() {
+            return default;
+        }
+
+        let read_ticks = |key: &str| -> u16 {
+            if !yaml["ticks"][key].is_badvalue() {
+                yaml["ticks"][key].as_i64().expect(format!("Could not read the {} ticks of the arc", key).as_str()) as u16
+            } else {
+                0
+            }
+        };
+
+        let read_bool = |key: &str| -> bool {
+            if !yaml[key].is_badvalue() {
+                yaml[key].as_bool().expect(format!("Could not read the arc {} parameter", key).as_str())
+            } else {
+                false
+            }
+        };
+
+        // Angles are given in degrees
+        let read_degrees = |key: &str, default: f32| -> f32 {
+            if !yaml[key].is_badvalue() {
+                yaml[key].as_f64().or(yaml[key].as_i64().map(|d| d as f64))
+                    .expect(format!("Could not read the arc {} parameter", key).as_str()) as f32
+            } else {
+                default
+            }
+        };
+
+        let sweep = read_degrees("sweep", DEFAULT_SWEEP_DEGREES);
+
+        if sweep <= 0.0 || sweep > 360.0 {
+            panic!("The arc sweep must be more than 0 and at most 360 degrees");
+        }
+
+        let colours = &yaml["colours"];
+
+        ArcStyle {
+            geometry: ArcGeometry {
+                sweep: sweep.to_radians(),
+                orientation: read_degrees("orientation", 0.0).to_radians(),
+            },
+            major_ticks: read_ticks("major"),
+            minor_ticks: read_ticks("minor"),
+            midpoint_marker: read_bool("midpoint_marker"),
+            needle: read_bool("needle"),
+            readout: read_bool("readout"),
+            track_colour: read_colour(colours, "track", default.track_colour),
+            left_colour: read_colour(colours, "left", default.left_colour),
+            right_colour: read_colour(colours, "right", default.right_colour),
+            disabled_colour: read_colour(colours, "disabled", default.disabled_colour),
+        }
+    }
+}
+
+/// How much of the current value the arc shows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayMode {
+    Visible,
+    /// Hidden until the decision is locked in and the arc is disabled
+    AfterLockIn,
+    /// Never shown, for blind tracking
+    Hidden,
+    /// Only the side of the anchor the value is on
+    Coarse,
+}
+
+impl Default for DisplayMode {
+    fn default() -> DisplayMode {
+        DisplayMode::Visible
+    }
+}
+
+impl DisplayMode {
+    /// Reads a screen's `display` entry: `visible` (the default), `after_lock_in`, `hidden` or `coarse`
+    pub fn from_yaml(yaml: &Yaml) -> DisplayMode {
+        if yaml.is_badvalue() {
+            return DisplayMode::default();
+        }
+
+        let mode = yaml.as_str().expect("Could not read the display mode of a screen");
+
+        match mode {
+            "visible" => DisplayMode::Visible,
+            "after_lock_in" => DisplayMode::AfterLockIn,
+            "hidden" => DisplayMode::Hidden,
+            "coarse" => DisplayMode::Coarse,
+            _ => panic!("Unknown display mode '{}' in the protocol", mode),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DisplayMode::Visible => "visible",
+            DisplayMode::AfterLockIn => "after_lock_in",
+            DisplayMode::Hidden => "hidden",
+            DisplayMode::Coarse => "coarse",
+        }
+    }
+}
+
+/// The device behind a pointer interaction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerKind {
+    Mouse,
+    Touch,
+}
+
+impl PointerKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PointerKind::Mouse => "mouse",
+            PointerKind::Touch => "touch",
+        }
+    }
+}
+
+/// Mouse or touch input on the arc, used instead of the dial
+#[derive(Debug, Clone, Copy)]
+pub enum PointerEvent {
+    /// The arc was pressed or dragged at the angle of this value
+    Value { kind: PointerKind, value: i32 },
+    /// Whole lines of mouse wheel movement over the arc, positive is clockwise
+    Rotated { steps: i32 },
+    /// A click or tap in the centre of the arc, which acts as the dial button
+    Pressed { kind: PointerKind },
+}
+
+impl PointerEvent {
+    pub fn kind(&self) -> PointerKind {
+        match self {
+            PointerEvent::Value { kind, .. } | PointerEvent::Pressed { kind } => *kind,
+            PointerEvent::Rotated { .. } => PointerKind::Mouse,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PointerState {
+    // Set while the arc is being dragged
+    dragging: Option<PointerKind>,
+    // Where on the scale the drag is, from 0 at the minimum to 1 at the maximum
+    drag_proportion: f32,
+    // Wheel movement smaller than one line, kept until it adds up to a step
+    wheel_remainder: f32,
+}
+
 pub struct ArcInput {
     value: i32,
     min_value: i32,
     max_value: i32,
-    mid_point: i32,
-    left_label: String,
-    right_label: String,
+    // The value the fill starts from
+    anchor: i32,
+    // Labels at values along the arc, e.g. the ends, the midpoint and points in between
+    labels: Vec<(i32, String)>,
     radius: f32,
     arc: Cache,
     disabled: bool,
     scale: f32,
+    style: ArcStyle,
+    display: DisplayMode,
 }
 
 impl ArcInput {
-    pub fn new(min: i32, max: i32, midpoint: i32, initial: i32, radius: f32) -> ArcInput {
+    pub fn new(min: i32, max: i32, anchor: i32, initial: i32, radius: f32) -> ArcInput {
         ArcInput {
             value: initial,
             min_value: min,
             max_value: max,
-            mid_point: midpoint,
-            left_label: "".to_string(),
-            right_label: "".to_string(),
+            anchor,
+            labels: Vec::new(),
             radius,
             arc: Cache::default(),
             disabled: false,
             scale: 1.0,
+            style: ArcStyle::default(),
+            display: DisplayMode::default(),
         }
     }
 
     pub fn view(&self) -> Element<Message> {
-        let rad = 1.2 * self.radius * self.scale;
-        let scale = self.scale;
-        let left_label = self.left_label.clone();
-        let right_label = self.right_label.clone();
-
-        Column::new()
-            .push(Canvas::new(self)
-                .width(Length::Units(rad as u16))
-                .height(Length::Units(rad as u16)))
-            .push(Row::new()
-                .width(Length::Units(rad as u16))
-                .push(Text::new(left_label).size((10.0 * scale) as u16).width(Length::Fill))
-                .push(Text::new(right_label).size((10.0 * scale) as u16).horizontal_alignment(Horizontal::Right).width(Length::Fill)))
+        let (width, height) = self.canvas_size();
+
+        Canvas::new(self)
+            .width(Length::Units(width as u16))
+            .height(Length::Units(height as u16))
             .into()
-        
     }
 
     pub fn set_value(&mut self, new_value: i32) {
@@ -60,12 +347,25 @@ impl ArcInput {
         self.request_redraw();
     }
 
+    /// Labels the minimum of the scale
     pub fn set_left_label(&mut self, new_label: String) {
-        self.left_label = new_label;
+        self.set_label(self.min_value, new_label);
     }
 
+    /// Labels the maximum of the scale
     pub fn set_right_label(&mut self, new_label: String) {
-        self.right_label = new_label;
+        self.set_label(self.max_value, new_label);
+    }
+
+    /// Places a label next to the arc at the angle of `value`, replacing any label already there
+    pub fn set_label(&mut self, value: i32, new_label: String) {
+        self.labels.retain(|(v, _)| *v != value);
+
+        if !new_label.is_empty() {
+            self.labels.push((value, new_label));
+        }
+
+        self.request_redraw();
     }
 
     pub fn request_redraw(&mut self) {
@@ -81,13 +381,251 @@ impl ArcInput {
         self.scale = scale;
     }
 
+    pub fn set_style(&mut self, style: ArcStyle) {
+        self.style = style;
+        self.request_redraw();
+    }
+
+    pub fn set_display(&mut self, display: DisplayMode) {
+        self.display = display;
+        self.request_redraw();
+    }
+
     pub fn is_disabled(&self) -> bool {
         self.disabled
     }
+
+    /// The value the fill runs to under the display mode, if it shows one
+    fn displayed_value(&self, value: i32) -> Option<i32> {
+        match self.display {
+            DisplayMode::Visible => Some(value),
+            DisplayMode::AfterLockIn if self.disabled => Some(value),
+            DisplayMode::AfterLockIn | DisplayMode::Hidden => None,
+            DisplayMode::Coarse => Some(if value < self.anchor {
+                self.min_value
+            } else if value > self.anchor {
+                self.max_value
+            } else {
+                self.anchor
+            }),
+        }
+    }
+
+    /// Whether the needle and the readout may show the exact value
+    fn shows_exact_value(&self) -> bool {
+        match self.display {
+            DisplayMode::Visible => true,
+            DisplayMode::AfterLockIn => self.disabled,
+            DisplayMode::Hidden | DisplayMode::Coarse => false,
+        }
+    }
+
+    fn label_size(&self) -> f32 {
+        10.0 * self.scale
+    }
+
+    /// The arc with room around it for the labels
+    fn canvas_size(&self) -> (f32, f32) {
+        let rad = 1.2 * self.radius * self.scale;
+
+        if self.labels.is_empty() {
+            return (rad, rad);
+        }
+
+        let longest = self.labels.iter().map(|(_, l)| l.chars().count()).max().unwrap_or(0) as f32;
+        let gap = LABEL_GAP * self.scale * self.radius/2.0;
+
+        (rad + 2.0 * (gap + longest * LABEL_CHAR_WIDTH * self.label_size()), rad + 2.0 * (gap + 1.5 * self.label_size()))
+    }
+
+    /// Draws the labels outside the arc, aligned so they extend away from it
+    fn draw_labels(&self, frame: &mut Frame, bounds: Rectangle) {
+        let distance = self.scale * self.radius/2.0 * (1.0 + LABEL_GAP);
+
+        for (value, label) in self.labels.iter() {
+            let angle = self.angle_of(*value as f32);
+
+            let horizontal_alignment = if angle.cos() > 0.3 {
+                Horizontal::Left
+            } else if angle.cos() < -0.3 {
+                Horizontal::Right
+            } else {
+                Horizontal::Center
+            };
+
+            let vertical_alignment = if angle.sin() > 0.3 {
+                Vertical::Top
+            } else if angle.sin() < -0.3 {
+                Vertical::Bottom
+            } else {
+                Vertical::Center
+            };
+
+            frame.fill_text(canvas::Text {
+                content: label.clone(),
+                position: self.point_at(bounds, angle, distance),
+                size: self.label_size(),
+                horizontal_alignment,
+                vertical_alignment,
+                ..canvas::Text::default()
+            });
+        }
+    }
+
+    /// Where a value sits on the scale, from 0 at the minimum to 1 at the maximum. Values outside the scale are clamped.
+    fn proportion_of(&self, value: f32) -> f32 {
+        if self.max_value == self.min_value {
+            return 0.0;
+        }
+
+        ((value - self.min_value as f32) / (self.max_value - self.min_value) as f32).clamp(0.0, 1.0)
+    }
+
+    /// The screen angle a value is drawn at
+    fn angle_of(&self, value: f32) -> f32 {
+        self.style.geometry.angle_of(self.proportion_of(value))
+    }
+
+    /// A point at `distance` from the centre of the canvas, in the direction of a screen angle
+    fn point_at(&self, bounds: Rectangle, angle: f32, distance: f32) -> Point {
+        Point::new(
+            bounds.width/2.0 + distance * angle.cos(),
+            bounds.height/2.0 + distance * angle.sin())
+    }
+
+    fn draw_ticks(&self, frame: &mut Frame, bounds: Rectangle) {
+        if self.style.major_ticks == 0 {
+            return;
+        }
+
+        let radius = self.scale * self.radius/2.0;
+        let minor_ticks = self.style.minor_ticks.max(1);
+        let ticks = self.style.major_ticks * minor_ticks;
+
+        // The last tick of a full circle would be drawn over the first
+        let last_tick = if self.style.geometry.is_full_circle() { ticks - 1 } else { ticks };
+
+        for tick in 0..=last_tick {
+            let major = tick % minor_ticks == 0;
+            let length = if major { MAJOR_TICK_LENGTH } else { MINOR_TICK_LENGTH };
+            let angle = self.style.geometry.angle_of(tick as f32 / ticks as f32);
+
+            let path = Path::line(self.point_at(bounds, angle, radius), self.point_at(bounds, angle, radius * (1.0 + length)));
+
+            frame.stroke(&path, Stroke {
+                style: Style::Solid(self.style.track_colour),
+                width: if major { 2.0 } else { 1.0 },
+                line_cap: LineCap::Round,
+                ..Stroke::default()
+            });
+        }
+    }
+
+    /// The value at the angle of a point on the canvas, for a pointer dragged from the proportion `dragged_from`
+    /// or pressed down. Both ends of a full circle are at its seam, so a point there reads as the end nearer
+    /// to where the drag came from, or to the current value for a press.
+    fn value_at(&self, bounds: Rectangle, point: Point, dragged_from: Option<f32>) -> i32 {
+        let geometry = self.style.geometry;
+        let angle = (point.y - bounds.height/2.0).atan2(point.x - bounds.width/2.0);
+
+        let proportion = match dragged_from {
+            Some(previous) => geometry.dragged_proportion(angle, previous),
+            None => geometry.proportion_at(angle),
+        };
+
+        let value = self.min_value + (proportion * (self.max_value - self.min_value) as f32).round() as i32;
+
+        if geometry.is_full_circle() && (value == self.min_value || value == self.max_value) {
+            let reference = dragged_from.unwrap_or_else(|| self.proportion_of(self.value as f32));
+            if reference < 0.5 { self.min_value } else { self.max_value }
+        } else {
+            value
+        }
+    }
+
+    /// Emits the value at a point and keeps its place on the scale for the rest of the drag
+    fn drag_to(&self, state: &mut PointerState, value: i32, kind: PointerKind) -> Option<PointerEvent> {
+        state.drag_proportion = self.proportion_of(value as f32);
+        Some(PointerEvent::Value { kind, value })
+    }
+
+    /// Starts a drag on the arc, or presses the button in its centre
+    fn pointer_down(&self, state: &mut PointerState, bounds: Rectangle, point: Point, kind: PointerKind) -> Option<PointerEvent> {
+        let distance = (point.x - bounds.width/2.0).hypot(point.y - bounds.height/2.0);
+
+        if distance < BUTTON_RADIUS * self.scale * self.radius/2.0 {
+            Some(PointerEvent::Pressed { kind })
+        } else if !self.disabled {
+            state.dragging = Some(kind);
+            self.drag_to(state, self.value_at(bounds, point, None), kind)
+        } else {
+            None
+        }
+    }
+
+    fn pointer_moved(&self, state: &mut PointerState, bounds: Rectangle, point: Point, kind: PointerKind) -> Option<PointerEvent> {
+        if state.dragging == Some(kind) && !self.disabled {
+            self.drag_to(state, self.value_at(bounds, point, Some(state.drag_proportion)), kind)
+        } else {
+            None
+        }
+    }
 }
 
 impl canvas::Program<Message> for ArcInput {
-    type State = ();
+    type State = PointerState;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> (event::Status, Option<Message>) {
+        let origin = bounds.position();
+        let relative = |position: Point| Point::new(position.x - origin.x, position.y - origin.y);
+
+        let pointer_event = match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                cursor.position_in(&bounds).and_then(|p| self.pointer_down(state, bounds, p, PointerKind::Mouse))
+            },
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                cursor.position_from(origin).and_then(|p| self.pointer_moved(state, bounds, p, PointerKind::Mouse))
+            },
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                state.dragging = None;
+                None
+            },
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) if cursor.is_over(&bounds) && !self.disabled => {
+                state.wheel_remainder += match delta {
+                    ScrollDelta::Lines { x: _, y } => y,
+                    // Touchpads scroll in pixels, roughly 20 of which make up a line
+                    ScrollDelta::Pixels { x: _, y } => y / 20.0,
+                };
+
+                let steps = state.wheel_remainder.trunc();
+                state.wheel_remainder -= steps;
+
+                if steps != 0.0 { Some(PointerEvent::Rotated { steps: steps as i32 }) } else { None }
+            },
+            Event::Touch(touch::Event::FingerPressed { id: _, position }) if bounds.contains(position) => {
+                self.pointer_down(state, bounds, relative(position), PointerKind::Touch)
+            },
+            Event::Touch(touch::Event::FingerMoved { id: _, position }) => {
+                self.pointer_moved(state, bounds, relative(position), PointerKind::Touch)
+            },
+            Event::Touch(touch::Event::FingerLifted { .. }) | Event::Touch(touch::Event::FingerLost { .. }) => {
+                state.dragging = None;
+                None
+            },
+            _ => None,
+        };
+
+        match pointer_event {
+            Some(e) => (event::Status::Captured, Some(Message::Pointer(e))),
+            None => (event::Status::Ignored, None),
+        }
+    }
 
     fn draw(
         &self,
@@ -100,12 +638,14 @@ impl canvas::Program<Message> for ArcInput {
             let mut arc_build = Builder::new();
             let mut fill_build = Builder::new();
 
+            let geometry = self.style.geometry;
+
             arc_build.ellipse(Elliptical {
                 center: Point::new(bounds.width/2.0, bounds.height/2.0),
                 radii: Vector::new(self.scale * self.radius/2.0, self.scale * self.radius/2.0),
-                rotation: 1.57,
-                start_angle: 0.785,
-                end_angle: 5.497,
+                rotation: 0.0,
+                start_angle: geometry.start(),
+                end_angle: geometry.angle_of(1.0),
             });
 
             let safe_value = if self.value > self.max_value {
@@ -116,39 +656,38 @@ impl canvas::Program<Message> for ArcInput {
                 self.value
             };
 
-            let proportion = ((safe_value as f32 + self.mid_point as f32) / (self.max_value - self.min_value) as f32 * 2.0);
-            let start_angle = 0.785 + (2.356 * (((self.mid_point as f32 - self.min_value as f32) / (self.max_value - self.min_value) as f32)) * 2.0);
+            // The fill runs from the anchor to the value, on whichever side of the anchor the value is
+            let anchor_angle = self.angle_of(self.anchor as f32);
+            let value_angle = self.angle_of(safe_value as f32);
 
-            fill_build.ellipse(Elliptical {
-                center: Point::new(bounds.width/2.0, bounds.height/2.0),
-                radii: Vector::new(self.scale * self.radius/2.0, self.scale * self.radius/2.0),
-                rotation: 1.57,
-                start_angle,
-                end_angle: start_angle + (2.356 * proportion),
-            });
+            if let Some(displayed) = self.displayed_value(safe_value) {
+                let displayed_angle = self.angle_of(displayed as f32);
+
+                fill_build.ellipse(Elliptical {
+                    center: Point::new(bounds.width/2.0, bounds.height/2.0),
+                    radii: Vector::new(self.scale * self.radius/2.0, self.scale * self.radius/2.0),
+                    rotation: 0.0,
+                    start_angle: anchor_angle.min(displayed_angle),
+                    end_angle: anchor_angle.max(displayed_angle),
+                });
+            }
 
             let arc_path = arc_build.build();
             let fill_path = fill_build.build();
 
             let arc_stroke = Stroke {
-                style: Style::Solid(Color::from_rgb(0.8, 0.8, 0.8)),
+                style: Style::Solid(self.style.track_colour),
                 width: 2.0,
                 line_cap: LineCap::Round,
                 ..Stroke::default()
             };
 
-            // let fill_color = if self.value == 0 {
-            //     Color::from_rgb(0.0, 0.0, 0.8)
-            // } else if self.value > 0 {
-            //     Color::from_rgb(0.0, 0.8, 0.0)
-            // } else {
-            //     Color::from_rgb(0.8, 0.0, 0.0)
-            // };
-
             let fill_color = if self.disabled {
-                Color::from_rgb(0.5, 0.5, 0.8)
+                self.style.disabled_colour
+            } else if safe_value < self.anchor {
+                self.style.left_colour
             } else {
-                Color::from_rgb(0.0, 0.0, 0.8)
+                self.style.right_colour
             };
 
             let fill_stroke = Stroke {
@@ -158,13 +697,211 @@ impl canvas::Program<Message> for ArcInput {
                 ..Stroke::default()
             };
 
+            let radius = self.scale * self.radius/2.0;
+
             frame.with_save(|frame| {
                 //frame.fill_rectangle(Point::new(0.0, 0.0), Size::new(frame.width(), frame.height()), Color::BLACK);
+                self.draw_ticks(frame, bounds);
+                self.draw_labels(frame, bounds);
                 frame.stroke(&arc_path, arc_stroke);
                 frame.stroke(&fill_path, fill_stroke);
+
+                if self.style.midpoint_marker {
+                    let angle = self.angle_of(self.anchor as f32);
+                    let marker = Path::line(self.point_at(bounds, angle, radius * (1.0 - MAJOR_TICK_LENGTH)), self.point_at(bounds, angle, radius * (1.0 + MAJOR_TICK_LENGTH)));
+
+                    frame.stroke(&marker, Stroke {
+                        style: Style::Solid(Color::from_rgb(0.3, 0.3, 0.3)),
+                        width: 3.0,
+                        line_cap: LineCap::Round,
+                        ..Stroke::default()
+                    });
+                }
+
+                if self.style.needle && self.shows_exact_value() {
+                    let needle = Path::line(Point::new(bounds.width/2.0, bounds.height/2.0), self.point_at(bounds, value_angle, radius * (1.0 - MAJOR_TICK_LENGTH)));
+
+                    frame.stroke(&needle, Stroke {
+                        style: Style::Solid(fill_color),
+                        width: 2.0,
+                        line_cap: LineCap::Round,
+                        ..Stroke::default()
+                    });
+                }
+
+                if self.style.readout && self.shows_exact_value() {
+                    frame.fill_text(canvas::Text {
+                        content: self.value.to_string(),
+                        position: Point::new(bounds.width/2.0, bounds.height/2.0),
+                        color: fill_color,
+                        size: 16.0 * self.scale,
+                        horizontal_alignment: Horizontal::Center,
+                        vertical_alignment: Vertical::Center,
+                        ..canvas::Text::default()
+                    });
+                }
             });
         });
 
         vec![arc]
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use iced_native::Size;
+
+    use super::*;
+
+    const RANGES: [(i32, i32); 3] = [(0, 100), (-100, 100), (-50, 50)];
+    const SWEEPS: [f32; 5] = [90.0, 180.0, 270.0, 300.0, 360.0];
+    const ORIENTATIONS: [f32; 3] = [0.0, 90.0, 180.0];
+
+    fn geometry(sweep: f32, orientation: f32) -> ArcGeometry {
+        ArcGeometry {
+            sweep: sweep.to_radians(),
+            orientation: orientation.to_radians(),
+        }
+    }
+
+    fn arc(min: i32, max: i32, anchor: i32, sweep: f32, orientation: f32) -> ArcInput {
+        let mut arc = ArcInput::new(min, max, anchor, anchor, 90.0);
+        arc.set_style(ArcStyle { geometry: geometry(sweep, orientation), ..ArcStyle::default() });
+        arc
+    }
+
+    fn bounds() -> Rectangle {
+        Rectangle::new(Point::ORIGIN, Size::new(200.0, 200.0))
+    }
+
+    /// The value read back from a press on the arc where `value` is drawn, while the arc shows `value`
+    fn round_trip(arc: &mut ArcInput, value: i32) -> i32 {
+        arc.set_value(value);
+        arc.value_at(bounds(), arc.point_at(bounds(), arc.angle_of(value as f32), 50.0), None)
+    }
+
+    /// The value read back from a drag, coming from `from`, to where `value` is drawn
+    fn dragged(arc: &ArcInput, from: i32, value: f32) -> i32 {
+        arc.value_at(bounds(), arc.point_at(bounds(), arc.angle_of(value), 50.0), Some(arc.proportion_of(from as f32)))
+    }
+
+    fn assert_round_trips(anchor: impl Fn(i32, i32) -> i32) {
+        for (min, max) in RANGES {
+            for sweep in SWEEPS {
+                for orientation in ORIENTATIONS {
+                    let mut arc = arc(min, max, anchor(min, max), sweep, orientation);
+
+                    for value in min..=max {
+                        assert_eq!(round_trip(&mut arc, value), value, "range {}..{}, sweep {}, orientation {}", min, max, sweep, orientation);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn values_round_trip_for_every_range() {
+        assert_round_trips(|min, _| min);
+    }
+
+    #[test]
+    fn values_round_trip_with_an_anchor_off_the_minimum() {
+        assert_round_trips(|min, max| (min + max) / 2 + 10);
+    }
+
+    #[test]
+    fn the_anchor_is_drawn_at_its_place_on_the_scale() {
+        let arc = arc(-50, 50, 10, 270.0, 0.0);
+
+        assert!((arc.proportion_of(10.0) - 0.6).abs() < 1e-6);
+        assert!((arc.angle_of(10.0) - arc.style.geometry.angle_of(0.6)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn the_middle_of_the_scale_points_in_the_orientation() {
+        for sweep in SWEEPS {
+            assert!((geometry(sweep, 0.0).angle_of(0.5) + FRAC_PI_2).abs() < 1e-5);
+            assert!((geometry(sweep, 180.0).angle_of(0.5) - FRAC_PI_2).abs() < 1e-5);
+            assert!((geometry(sweep, 90.0).angle_of(0.5)).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn the_ends_of_the_scale_are_a_sweep_apart() {
+        for sweep in SWEEPS {
+            for orientation in ORIENTATIONS {
+                let geometry = geometry(sweep, orientation);
+                assert!((geometry.angle_of(1.0) - geometry.angle_of(0.0) - sweep.to_radians()).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn values_outside_the_scale_are_clamped() {
+        for (min, max) in RANGES {
+            for orientation in ORIENTATIONS {
+                let mut arc = arc(min, max, min, 270.0, orientation);
+
+                assert_eq!(arc.proportion_of((min - 50) as f32), 0.0);
+                assert_eq!(arc.proportion_of((max + 50) as f32), 1.0);
+                assert_eq!(round_trip(&mut arc, min - 50), min);
+                assert_eq!(round_trip(&mut arc, max + 50), max);
+            }
+        }
+    }
+
+    #[test]
+    fn angles_in_the_gap_go_to_the_nearest_end() {
+        for (min, max) in RANGES {
+            for sweep in [90.0, 180.0, 270.0] {
+                for orientation in ORIENTATIONS {
+                    let arc = arc(min, max, min, sweep, orientation);
+                    let geometry = arc.style.geometry;
+                    let gap = TAU - geometry.sweep;
+
+                    let near_min = arc.point_at(bounds(), geometry.start() - 0.25 * gap, 50.0);
+                    let near_max = arc.point_at(bounds(), geometry.start() + geometry.sweep + 0.25 * gap, 50.0);
+
+                    assert_eq!(arc.value_at(bounds(), near_min, None), min);
+                    assert_eq!(arc.value_at(bounds(), near_max, None), max);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_press_on_the_seam_of_a_full_circle_reads_as_the_end_nearer_the_value() {
+        for (min, max) in RANGES {
+            for orientation in ORIENTATIONS {
+                let mut arc = arc(min, max, min, 360.0, orientation);
+                let seam = arc.point_at(bounds(), arc.style.geometry.start(), 50.0);
+
+                arc.set_value(min + (max - min) / 4);
+                assert_eq!(arc.value_at(bounds(), seam, None), min);
+
+                arc.set_value(max - (max - min) / 4);
+                assert_eq!(arc.value_at(bounds(), seam, None), max);
+            }
+        }
+    }
+
+    #[test]
+    fn a_drag_across_the_seam_of_a_full_circle_stays_at_its_end() {
+        for (min, max) in RANGES {
+            for orientation in ORIENTATIONS {
+                let arc = arc(min, max, min, 360.0, orientation);
+                let quarter = (max - min) / 4;
+
+                // Past the maximum onto the start of the scale, and back past the minimum onto its end
+                assert_eq!(dragged(&arc, max - 1, (min + 1) as f32), max);
+                assert_eq!(dragged(&arc, max, (min + quarter) as f32), max);
+                assert_eq!(dragged(&arc, min + 1, (max - 1) as f32), min);
+                assert_eq!(dragged(&arc, min, (max - quarter) as f32), min);
+
+                // Moves that don't cross the seam follow the pointer
+                assert_eq!(dragged(&arc, max - 1, (max - quarter) as f32), max - quarter);
+                assert_eq!(dragged(&arc, min + quarter, (min + 1) as f32), min + 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! Builds the analysis reports from the tidy tables in `output/`:
+//!
+//! - `participant_<id>.csv`: demographics and every decision of every trial (replaces convert_data.py)
+//! - `ddm.csv`: response time and choice of each dynamic trial for drift diffusion modelling (replaces ddm_data.py)
+//! - `comparison.csv`: dynamic and dichotomous decisions side by side per stimulus (replaces data_conversion_script.py)
+//!
+//! Binary decisions made final by the end of the stimulus before a side was picked are `undecided`. They are
+//! copied into the participant and comparison reports as they are, and left out of the DDM report.
+//!
+//! Usage: `ddm-export [output folder] [reports folder]`
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{create_dir_all, read_dir, read_to_string, File};
+use std::io::Write;
+use std::path::Path;
+
+#[allow(dead_code)]
+#[path = "../data/schema.rs"]
+mod schema;
+
+use schema::*;
+
+/// Every row belonging to one trial of one participant
+#[derive(Default)]
+struct TrialRows {
+    stimulus: String,
+    ground_truth: String,
+    condition: String,
+    dynamic: Vec<TidyRow>,
+    dichotomous: Vec<TidyRow>,
+    confidence: Vec<TidyRow>,
+}
+
+struct Participant {
+    id: usize,
+    responses: Vec<TidyRow>,
+    trials: BTreeMap<usize, TrialRows>,
+}
+
+/// The name of a trial screen without its `_<trial>` suffix
+fn screen_prefix(screen: &str) -> &str {
+    match screen.rsplit_once('_') {
+        Some((prefix, suffix)) if suffix.parse::<usize>().is_ok() => prefix,
+        _ => screen,
+    }
+}
+
+impl Participant {
+    fn from_rows(id: usize, rows: Vec<TidyRow>) -> Participant {
+        let mut participant = Participant {
+            id,
+            responses: Vec::new(),
+            trials: BTreeMap::new(),
+        };
+
+        for row in rows {
+            let trial_index = match row.trial {
+                Some(t) => t,
+                None => {
+                    if row.event == EVENT_RESPONSE || row.event == EVENT_RESPONSE_LABEL {
+                        participant.responses.push(row);
+                    }
+                    continue;
+                }
+            };
+
+            let trial = participant.trials.entry(trial_index).or_default();
+
+            match row.event.as_str() {
+                EVENT_STIMULUS => trial.stimulus = row.value.clone(),
+                EVENT_GROUND_TRUTH => trial.ground_truth = row.value.clone(),
+                EVENT_CONDITION => trial.condition = row.value.clone(),
+                _ => {}
+            }
+
+            match screen_prefix(&row.screen) {
+                SCREEN_DYNAMIC => trial.dynamic.push(row),
+                SCREEN_DICHOTOMOUS => trial.dichotomous.push(row),
+                SCREEN_CONFIDENCE => trial.confidence.push(row),
+                _ => {}
+            }
+        }
+
+        participant
+    }
+}
+
+fn find_event<'a>(rows: &'a [TidyRow], event: &str) -> Option<&'a TidyRow> {
+    rows.iter().find(|r| r.event == event)
+}
+
+fn format_optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn seconds(row: &TidyRow) -> String {
+    format_optional(row.timestamp.map(|t| t as f64 / 1000.0))
+}
+
+/// The last settled dynamic decision, or the final one if the participant never settled
+fn last_dynamic_decision(trial: &TrialRows) -> Option<&TidyRow> {
+    trial.dynamic.iter()
+        .filter(|r| r.event == EVENT_DECISION)
+        .last()
+        .or_else(|| find_event(&trial.dynamic, EVENT_FINAL))
+}
+
+fn write_report(path: &Path, headers: &str, lines: &[String]) {
+    let mut file = File::create(path).expect("Could not open report for writing");
+
+    file.write_all(headers.as_bytes()).expect("Could not write CSV header to file");
+    file.write_all("\n".as_bytes()).expect("Could not write to file");
+
+    for line in lines.iter() {
+        file.write_all(line.as_bytes()).expect("Could not write CSV data");
+        file.write_all("\n".as_bytes()).expect("Could not write to file");
+    }
+
+    println!("Wrote {}", path.display());
+}
+
+fn participant_report(participant: &Participant, reports: &Path) {
+    let mut lines: Vec<String> = Vec::new();
+
+    for response in participant.responses.iter() {
+        lines.push(format!(",,,{},{},,{},", response.screen, response.event, response.value));
+    }
+
+    for (index, trial) in participant.trials.iter() {
+        for row in trial.dynamic.iter().chain(trial.dichotomous.iter()).chain(trial.confidence.iter()) {
+            if row.event != EVENT_DECISION && row.event != EVENT_FINAL {
+                continue;
+            }
+
+            lines.push(format!("{},{},{},{},{},{},{},{}",
+                index,
+                trial.stimulus,
+                trial.ground_truth,
+                row.screen,
+                row.event,
+                format_optional(row.timestamp),
+                row.value,
+                format_optional(row.velocity)));
+        }
+    }
+
+    write_report(
+        &reports.join(format!("participant_{}.csv", participant.id)),
+        "trial,stimulus,ground_truth,screen,event,timestamp,value,velocity",
+        &lines);
+}
+
+/// Choice is 1 for lie and 2 for truth, response time is the final decision in seconds. Only trials of
+/// the dynamic condition are used, in the dichotomous condition the video ends the dynamic decision.
+fn ddm_report(participants: &[Participant], reports: &Path) {
+    let mut lines: Vec<String> = Vec::new();
+
+    for participant in participants.iter() {
+        for trial in participant.trials.values().filter(|t| t.condition == CONDITION_DYNAMIC) {
+            if let Some(final_row) = find_event(&trial.dynamic, EVENT_FINAL) {
+                let choice = match final_row.value.parse::<i32>() {
+                    Ok(v) if v < 0 => 1,
+                    Ok(_) => 2,
+                    Err(_) => continue,
+                };
+
+                lines.push(format!("{},{},{},{},{},{}", trial.stimulus, trial.ground_truth, participant.id, trial.condition, seconds(final_row), choice));
+            }
+        }
+    }
+
+    lines.sort();
+
+    write_report(&reports.join("ddm.csv"), "stimulus,ground_truth,participant,condition,rt,choice", &lines);
+}
+
+fn comparison_report(participants: &[Participant], reports: &Path) {
+    let mut lines: Vec<String> = Vec::new();
+
+    for participant in participants.iter() {
+        for trial in participant.trials.values() {
+            let dynamic = last_dynamic_decision(trial);
+            let dichotomous = find_event(&trial.dichotomous, EVENT_FINAL);
+
+            // Participants who also made a dichotomous decision had their dynamic decision made final by the end of the video
+            let columns = if dichotomous.is_some() {
+                [
+                    format_optional(dynamic.map(|r| r.value.clone())),
+                    format_optional(dynamic.map(seconds)),
+                    format_optional(dichotomous.map(|r| r.value.clone())),
+                    format_optional(dichotomous.map(seconds)),
+                    String::default(),
+                    String::default(),
+                ]
+            } else {
+                [
+                    String::default(),
+                    String::default(),
+                    String::default(),
+                    String::default(),
+                    format_optional(dynamic.map(|r| r.value.clone())),
+                    format_optional(dynamic.map(seconds)),
+                ]
+            };
+
+            lines.push(format!("{},{},{},{},{}", trial.stimulus, trial.ground_truth, participant.id, trial.condition, columns.join(",")));
+        }
+    }
+
+    lines.sort();
+
+    write_report(
+        &reports.join("comparison.csv"),
+        "stimulus,ground_truth,participant,condition,di_decision,di_decision_rt,di_final,di_final_rt,dyn_decision,dyn_rt",
+        &lines);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let output = Path::new(args.get(1).map(|a| a.as_str()).unwrap_or(OUTPUT_FOLDER));
+    let reports = Path::new(args.get(2).map(|a| a.as_str()).unwrap_or("reports"));
+
+    create_dir_all(reports).expect("Could not create the reports folder");
+
+    let mut participants: Vec<Participant> = Vec::new();
+
+    for entry in read_dir(output).expect("Could not read the output folder") {
+        let path = entry.expect("Could not read the output folder").path();
+
+        let id = match path.file_name().and_then(|n| n.to_str()).and_then(|n| n.parse::<usize>().ok()) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        match read_to_string(path.join(PARTICIPANT_FILE)) {
+            Ok(contents) => {
+                println!("Reading participant data in {}", path.display());
+                participants.push(Participant::from_rows(id, TidyRow::read_all(contents.as_str())));
+            },
+            Err(_) => println!("Skipping {}, it has no {}", path.display(), PARTICIPANT_FILE),
+        }
+    }
+
+    participants.sort_by_key(|p| p.id);
+
+    for participant in participants.iter() {
+        participant_report(participant, reports);
+    }
+
+    ddm_report(&participants, reports);
+    comparison_report(&participants, reports);
+}
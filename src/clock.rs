@@ -0,0 +1,51 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+thread_local! {
+    // Start of the running replay and how far into its script it is
+    static REPLAY: Cell<Option<(Instant, Duration)>> = const { Cell::new(None) };
+}
+
+/// The time screens stamp their data with. It is the monotonic clock, except on a thread running a replay,
+/// where it follows the script so every run of the replay writes the same timestamps.
+pub fn now() -> Instant {
+    REPLAY.with(|r| match r.get() {
+        Some((start, elapsed)) => start + elapsed,
+        None => Instant::now(),
+    })
+}
+
+/// The date and time of `now()`. A replay starts at the Unix epoch.
+pub fn system_time() -> SystemTime {
+    REPLAY.with(|r| match r.get() {
+        Some((_, elapsed)) => UNIX_EPOCH + elapsed,
+        None => SystemTime::now(),
+    })
+}
+
+/// Takes over `now()` on this thread until it is dropped
+pub struct ReplayClock {
+    start: Instant,
+}
+
+impl ReplayClock {
+    pub fn start() -> ReplayClock {
+        let start = Instant::now();
+        REPLAY.with(|r| r.set(Some((start, Duration::ZERO))));
+
+        ReplayClock {
+            start
+        }
+    }
+
+    /// Moves the clock to a time since the start of the replay
+    pub fn set(&self, elapsed: Duration) {
+        REPLAY.with(|r| r.set(Some((self.start, elapsed))));
+    }
+}
+
+impl Drop for ReplayClock {
+    fn drop(&mut self) {
+        REPLAY.with(|r| r.set(None));
+    }
+}
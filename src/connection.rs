@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+use surface_dial_rs::events::{ConnectionEvent, TopLevelEvent};
+use yaml_rust::Yaml;
+
+use crate::data::schema::{EVENT_COMPROMISED, EVENT_DISCONNECTED, EVENT_RECONNECTED};
+use crate::views::DataRecord;
+
+/// What happens to the current trial once the dial reconnects
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReconnectPolicy {
+    /// Carry on where the trial was paused
+    Resume,
+    /// Carry on, but mark the trial as compromised so it can be excluded from the analysis
+    Flag,
+}
+
+impl ReconnectPolicy {
+    /// Reads `config: on_reconnect`, which is `resume` (the default) or `flag`
+    pub fn from_config(yaml_config: &Yaml) -> ReconnectPolicy {
+        let policy = if !yaml_config["config"]["on_reconnect"].is_badvalue() {
+            yaml_config["config"]["on_reconnect"].as_str().expect("Could not read on_reconnect from config")
+        } else {
+            "resume"
+        };
+
+        match policy {
+            "resume" => ReconnectPolicy::Resume,
+            "flag" => ReconnectPolicy::Flag,
+            _ => panic!("Unknown on_reconnect policy '{}' in config", policy),
+        }
+    }
+}
+
+/// A change of the dial connection, passed on to the current screen
+#[derive(Debug, Clone, Copy)]
+pub enum ConnectionChange {
+    Lost { at: Instant },
+    Restored { at: Instant, outage: Duration, compromised: bool },
+}
+
+/// Keeps track of whether the dial is connected for the whole program
+pub struct ConnectionMonitor {
+    policy: ReconnectPolicy,
+    disconnected_since: Option<Instant>,
+}
+
+impl ConnectionMonitor {
+    pub fn new(policy: ReconnectPolicy) -> ConnectionMonitor {
+        ConnectionMonitor {
+            policy,
+            disconnected_since: None,
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.disconnected_since.is_none()
+    }
+
+    /// Returns the change if the event connects or disconnects the dial. Repeated events are ignored.
+    pub fn handle(&mut self, event: &TopLevelEvent, at: Instant) -> Option<ConnectionChange> {
+        match event {
+            TopLevelEvent::ConnectionEvent(ConnectionEvent::Disconnected) if self.is_connected() => {
+                println!("The dial disconnected");
+                self.disconnected_since = Some(at);
+                Some(ConnectionChange::Lost { at })
+            },
+            TopLevelEvent::ConnectionEvent(ConnectionEvent::Connected) => {
+                let since = self.disconnected_since.take()?;
+                let outage = at.saturating_duration_since(since);
+                println!("The dial reconnected after {} ms", outage.as_millis());
+
+                Some(ConnectionChange::Restored {
+                    at,
+                    outage,
+                    compromised: self.policy == ReconnectPolicy::Flag,
+                })
+            },
+            _ => None,
+        }
+    }
+}
+
+struct Outage {
+    start: u128,
+    // Timestamp of the reconnection and the length of the outage in ms
+    end: Option<(u128, u128)>,
+}
+
+/// Outages during a trial, in the screen's own timestamps (video position or time since shown)
+pub struct ConnectionLog {
+    outages: Vec<Outage>,
+    compromised: bool,
+}
+
+impl ConnectionLog {
+    pub fn new() -> ConnectionLog {
+        ConnectionLog {
+            outages: Vec::new(),
+            compromised: false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.outages.clear();
+        self.compromised = false;
+    }
+
+    pub fn lost(&mut self, timestamp: u128) {
+        self.outages.push(Outage { start: timestamp, end: None });
+    }
+
+    /// The outage is passed in, since screens with a video stop their clock while the dial is disconnected
+    pub fn restored(&mut self, timestamp: u128, outage: Duration, compromised: bool) {
+        if let Some(last) = self.outages.last_mut() {
+            last.end = Some((timestamp, outage.as_millis()));
+        }
+
+        self.compromised |= compromised;
+    }
+
+    /// The value of a reconnection is the length of the outage in ms
+    pub fn to_records(&self, trial: Option<usize>) -> Vec<DataRecord> {
+        let mut records: Vec<DataRecord> = Vec::new();
+
+        for outage in self.outages.iter() {
+            records.push(DataRecord::sample(trial, EVENT_DISCONNECTED, outage.start, "0".to_string(), None));
+
+            if let Some((end, length)) = outage.end {
+                records.push(DataRecord::sample(trial, EVENT_RECONNECTED, end, length.to_string(), None));
+            }
+        }
+
+        if self.compromised {
+            records.push(DataRecord::metadata(trial, EVENT_COMPROMISED, "true".to_string()));
+        }
+
+        records
+    }
+}
@@ -0,0 +1,130 @@
+use std::fs::{create_dir_all, read_to_string, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::phase::PhasePosition;
+use crate::trials::Trial;
+
+const JOURNAL_HEADERS: &'static str = "type,key,value";
+
+/// Append-only record of a participant's session, written to `<output>/<id>/session_journal.csv`.
+/// Every line is flushed to disk immediately so a crashed session can be resumed with the same trials.
+pub struct SessionJournal {
+    path: PathBuf,
+}
+
+/// Everything recovered from an existing journal
+pub struct JournalState {
+    pub counterbalance: bool,
+    pub condition: String,
+    pub stimulus_ids: Vec<String>,
+    pub trial_uuids: Vec<String>,
+    pub completed: Vec<PhasePosition>,
+    pub finished: bool,
+}
+
+impl JournalState {
+    /// The furthest screen the participant completed, if any
+    pub fn last_completed(&self) -> Option<PhasePosition> {
+        self.completed.iter().max().copied()
+    }
+}
+
+fn journal_path(output: &Path, id: usize) -> PathBuf {
+    output.join(id.to_string()).join("session_journal.csv")
+}
+
+impl SessionJournal {
+    /// Starts a new journal for the participant, replacing any previous one
+    pub fn create(output: &Path, id: usize, seed: u64, counterbalance: bool, condition: &str, trials: &[Trial]) -> SessionJournal {
+        let path = journal_path(output, id);
+
+        create_dir_all(path.parent().unwrap()).expect("Could not create the participant output folder");
+        File::create(&path).expect("Could not create the session journal");
+
+        let journal = SessionJournal { path };
+
+        journal.append(JOURNAL_HEADERS);
+        journal.append(format!("seed,,{}", seed).as_str());
+        journal.append(format!("counterbalance,,{}", counterbalance).as_str());
+        journal.append(format!("condition,,{}", condition).as_str());
+
+        for (i, trial) in trials.iter().enumerate() {
+            journal.append(format!("trial,{},{}", i, trial.stimulus.id).as_str());
+            journal.append(format!("trial_uuid,{},{}", i, trial.uuid).as_str());
+        }
+
+        journal
+    }
+
+    /// Continues writing to the journal of a session that is being resumed
+    pub fn reopen(output: &Path, id: usize) -> SessionJournal {
+        SessionJournal { path: journal_path(output, id) }
+    }
+
+    /// Reads the journal of a previous session, if the participant has one
+    pub fn load(output: &Path, id: usize) -> Option<JournalState> {
+        let contents = read_to_string(journal_path(output, id)).ok()?;
+
+        let mut state = JournalState {
+            counterbalance: false,
+            condition: String::default(),
+            stimulus_ids: Vec::new(),
+            trial_uuids: Vec::new(),
+            completed: Vec::new(),
+            finished: false,
+        };
+
+        for line in contents.lines().skip(1) {
+            let columns: Vec<&str> = line.splitn(3, ',').collect();
+
+            if columns.len() < 3 {
+                continue;
+            }
+
+            match columns[0] {
+                "counterbalance" => state.counterbalance = columns[2] == "true",
+                "condition" => state.condition = columns[2].to_string(),
+                "trial" => state.stimulus_ids.push(columns[2].to_string()),
+                "trial_uuid" => state.trial_uuids.push(columns[2].to_string()),
+                "completed" => {
+                    let position: Vec<&str> = columns[1].split(':').collect();
+
+                    if let (Some(phase), Some(screen)) = (position.get(0), position.get(1)) {
+                        if let (Ok(phase), Ok(screen)) = (phase.parse::<usize>(), screen.parse::<usize>()) {
+                            state.completed.push(PhasePosition { phase, screen });
+                        }
+                    }
+                },
+                "finished" => state.finished = true,
+                _ => {}
+            }
+        }
+
+        Some(state)
+    }
+
+    /// Stores when the session was started or resumed, in milliseconds since the Unix epoch.
+    /// The raw event timestamps of the screens shown after it count from this moment.
+    pub fn record_session_start(&self, at: SystemTime) {
+        let unix_ms = at.duration_since(UNIX_EPOCH).expect("The system clock is before the Unix epoch").as_millis();
+        self.append(format!("session_start,,{}", unix_ms).as_str());
+    }
+
+    pub fn record_completed(&self, position: PhasePosition, phase_name: &str) {
+        self.append(format!("completed,{}:{},{}", position.phase, position.screen, phase_name).as_str());
+    }
+
+    pub fn record_finished(&self) {
+        self.append("finished,,");
+    }
+
+    fn append(&self, line: &str) {
+        let mut file = OpenOptions::new().append(true).open(&self.path).expect("Could not open the session journal");
+
+        file.write_all(line.as_bytes()).expect("Could not write to the session journal");
+        file.write_all("\n".as_bytes()).expect("Could not write to the session journal");
+        file.sync_data().expect("Could not flush the session journal to the disk");
+    }
+}
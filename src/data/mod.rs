@@ -1,12 +1,17 @@
-use std::{path::Path, fs::File, io::Write, fs::create_dir_all};
+use std::{path::Path, fs::File, io::Write, fs::create_dir_all, fs::rename};
 
 use crate::views::ExperimentData;
 
+pub mod journal;
 pub mod partipant_data;
+pub mod raw;
+pub mod schema;
+pub mod tidy;
 
-pub fn write_data_file(id: usize, experiment_data: Box<&dyn ExperimentData>) {
-    let file_name = format!("output/{}/{}.csv", id, experiment_data.name());
-    let write_path = Path::new(file_name.as_str());
+/// Writes a screen's data to `<output>/<id>/<name>.csv`
+pub fn write_data_file(output: &Path, id: usize, experiment_data: Box<&dyn ExperimentData>) {
+    let file_name = output.join(id.to_string()).join(format!("{}.csv", experiment_data.name()));
+    let write_path = file_name.as_path();
 
     let parent_folder = write_path.parent().unwrap();
 
@@ -14,10 +19,27 @@ pub fn write_data_file(id: usize, experiment_data: Box<&dyn ExperimentData>) {
         create_dir_all(parent_folder).unwrap();
     }
 
+    // A screen revisited through back navigation is written again, so keep the earlier answers
+    if write_path.exists() {
+        archive_revision(&experiment_data.name(), write_path);
+    }
+
     let mut file = File::create(write_path).expect("Could not open data file for writing");
 
     file.write_all(experiment_data.headers().as_bytes()).expect("Could not write CSV header to file");
     file.write("\n".as_bytes()).expect("Could not write to file");
     file.write_all(experiment_data.data().to_csv().as_bytes()).expect("Could not write CSV data");
     file.flush().expect("Could not flush the file to the disk");
-}
\ No newline at end of file
+}
+
+/// Renames the existing data file to `<name>.rev<n>.csv` next to it, so the analysis scripts skip it
+fn archive_revision(name: &str, current_path: &Path) {
+    let revision_path = |revision: usize| current_path.with_file_name(format!("{}.rev{}.csv", name, revision));
+
+    let mut revision = 1;
+    while revision_path(revision).exists() {
+        revision += 1;
+    }
+
+    rename(current_path, revision_path(revision)).expect("Could not archive the previous data file");
+}
@@ -0,0 +1,158 @@
+use std::time::{Duration, Instant};
+
+use surface_dial_rs::events::{DialDirection, DialEvent, TopLevelEvent};
+
+use crate::arc_input::PointerEvent;
+use crate::views::{DataRecord, ExperimentData, Printable};
+
+struct RawEvent {
+    timestamp_ns: u128,
+    position_ms: u128,
+    event: String,
+    direction: Option<&'static str>,
+    velocity: Option<f32>,
+    pressed: Option<bool>,
+    value: Option<i32>,
+}
+
+/// Every dial event a screen received, unfiltered, written to `<screen>_raw.csv` next to the settled decisions
+pub struct RawEventLog {
+    name: String,
+    trial: Option<usize>,
+    // Start of the session, which the timestamps of every raw event log of the session count from
+    session_start: Instant,
+    events: Vec<RawEvent>,
+}
+
+fn optional<T: ToString>(field: &Option<T>) -> String {
+    match field {
+        Some(f) => f.to_string(),
+        None => String::default(),
+    }
+}
+
+impl RawEventLog {
+    pub fn new(screen_name: String, trial: Option<usize>, session_start: Instant) -> RawEventLog {
+        RawEventLog {
+            name: format!("{}_raw", screen_name),
+            trial,
+            session_start,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Nanoseconds on the monotonic clock from the start of the session up to `at`
+    fn monotonic_ns(&self, at: Instant) -> u128 {
+        at.saturating_duration_since(self.session_start).as_nanos()
+    }
+
+    /// Logs an event that arrived `at`, when the video position or the time since the screen was shown was `position`
+    pub fn record(&mut self, event: &TopLevelEvent, position: Duration, at: Instant) {
+        let timestamp_ns = self.monotonic_ns(at);
+        let position_ms = position.as_millis();
+
+        let raw_event = match event {
+            TopLevelEvent::DialEvent(DialEvent::Rotate { direction, velocity }) => RawEvent {
+                timestamp_ns,
+                position_ms,
+                event: "rotate".to_string(),
+                direction: Some(match direction {
+                    DialDirection::Clockwise => "cw",
+                    DialDirection::Counterclockwise => "ccw",
+                }),
+                velocity: Some(*velocity as f32),
+                pressed: None,
+                value: None,
+            },
+            TopLevelEvent::DialEvent(DialEvent::Button { pressed }) => RawEvent {
+                timestamp_ns,
+                position_ms,
+                event: "button".to_string(),
+                direction: None,
+                velocity: None,
+                pressed: Some(*pressed),
+                value: None,
+            },
+            TopLevelEvent::ConnectionEvent(c) => RawEvent {
+                timestamp_ns,
+                position_ms,
+                event: format!("{:?}", c).to_lowercase(),
+                direction: None,
+                velocity: None,
+                pressed: None,
+                value: None,
+            },
+        };
+
+        self.events.push(raw_event);
+    }
+
+    /// Logs mouse or touch input on the arc, as `<kind>_value`, `<kind>_rotate` or `<kind>_button` events
+    pub fn record_pointer(&mut self, event: &PointerEvent, position: Duration, at: Instant) {
+        let kind = event.kind().name();
+
+        let (name, direction, pressed, value) = match event {
+            PointerEvent::Value { kind: _, value } => ("value", None, None, Some(*value)),
+            PointerEvent::Rotated { steps } => ("rotate", Some(if *steps > 0 { "cw" } else { "ccw" }), None, Some(steps.abs())),
+            PointerEvent::Pressed { kind: _ } => ("button", None, Some(true), None),
+        };
+
+        self.events.push(RawEvent {
+            timestamp_ns: self.monotonic_ns(at),
+            position_ms: position.as_millis(),
+            event: format!("{}_{}", kind, name),
+            direction,
+            velocity: None,
+            pressed,
+            value,
+        });
+    }
+}
+
+impl ExperimentData for RawEventLog {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn headers(&self) -> String {
+        "timestamp_ns,position_ms,event,direction,velocity,pressed,value".to_string()
+    }
+
+    fn data(&self) -> Box<&dyn Printable> {
+        Box::new(self)
+    }
+
+    fn records(&self) -> Vec<DataRecord> {
+        self.events.iter()
+            .map(|e| DataRecord::sample(
+                self.trial,
+                format!("raw_{}", e.event).as_str(),
+                e.position_ms,
+                e.direction.map(|d| d.to_string()).or(e.pressed.map(|p| p.to_string())).or(e.value.map(|v| v.to_string())).unwrap_or_default(),
+                e.velocity))
+            .collect()
+    }
+}
+
+impl Printable for RawEventLog {
+    fn to_csv(&self) -> String {
+        let mut final_string: String = "".to_string();
+
+        for e in self.events.iter() {
+            final_string.push_str(format!("{},{},{},{},{},{},{}\n",
+                e.timestamp_ns,
+                e.position_ms,
+                e.event,
+                optional(&e.direction),
+                optional(&e.velocity),
+                optional(&e.pressed),
+                optional(&e.value)).as_str());
+        }
+
+        final_string
+    }
+}
@@ -0,0 +1,142 @@
+//! Layout of the tidy long-format output. This file only depends on `std` so the export tool can share it.
+
+pub const TIDY_HEADERS: &'static str = "participant,trial,screen,event,timestamp,value,velocity";
+pub const OUTPUT_FOLDER: &'static str = "output";
+// Participant tables are in `<output>/<id>/`, the study table is in the output folder itself
+pub const PARTICIPANT_FILE: &'static str = "session_long.csv";
+pub const STUDY_FILE: &'static str = "study_long.csv";
+
+// Screen names are `<prefix>_<trial>`
+pub const SCREEN_DYNAMIC: &'static str = "lie_truth_dynamic";
+pub const SCREEN_DICHOTOMOUS: &'static str = "lie_truth_dichotomous";
+pub const SCREEN_CONFIDENCE: &'static str = "confidence";
+
+// The participant condition in which the dynamic decision is final
+pub const CONDITION_DYNAMIC: &'static str = "dynamic";
+
+// Event types shared by the views and the export tool
+pub const EVENT_DECISION: &'static str = "decision";
+pub const EVENT_FINAL: &'static str = "final";
+pub const EVENT_RESPONSE: &'static str = "response";
+pub const EVENT_RESPONSE_LABEL: &'static str = "response_label";
+pub const EVENT_PATH: &'static str = "path";
+pub const EVENT_COUNTERBALANCE: &'static str = "counterbalance";
+pub const EVENT_TRIAL_UUID: &'static str = "trial_uuid";
+pub const EVENT_STIMULUS: &'static str = "stimulus";
+pub const EVENT_GROUND_TRUTH: &'static str = "ground_truth";
+pub const EVENT_SPEAKER: &'static str = "speaker";
+pub const EVENT_DURATION: &'static str = "duration_ms";
+pub const EVENT_TAGS: &'static str = "tags";
+pub const EVENT_POSITION: &'static str = "position";
+pub const EVENT_CONDITION: &'static str = "condition";
+pub const EVENT_DISCONNECTED: &'static str = "disconnected";
+pub const EVENT_RECONNECTED: &'static str = "reconnected";
+pub const EVENT_COMPROMISED: &'static str = "compromised";
+pub const EVENT_HAPTIC_ENABLED: &'static str = "haptic_enabled";
+pub const EVENT_HAPTIC_DETENTS: &'static str = "haptic_detents";
+pub const EVENT_MAPPING: &'static str = "mapping";
+pub const EVENT_MAPPING_STEP: &'static str = "mapping_step";
+pub const EVENT_MAPPING_GAIN: &'static str = "mapping_gain";
+pub const EVENT_MAPPING_MAX_STEP: &'static str = "mapping_max_step";
+pub const EVENT_INPUT: &'static str = "input";
+pub const EVENT_DISPLAY: &'static str = "display";
+pub const EVENT_LOCK_IN_POLICY: &'static str = "lock_in_policy";
+pub const EVENT_LOCK_IN_MIN_VIEWING: &'static str = "lock_in_min_viewing_ms";
+pub const EVENT_LOCK_IN_CONFIRM: &'static str = "lock_in_confirm";
+pub const EVENT_LOCK_IN_REVISION: &'static str = "lock_in_revision_window_ms";
+pub const EVENT_LOCK_IN_MAX_CHANGES: &'static str = "lock_in_max_changes";
+pub const EVENT_LOCK_IN: &'static str = "lock_in";
+pub const EVENT_SIDE_CHANGE: &'static str = "side_change";
+pub const EVENT_SIDE_CHANGES: &'static str = "side_changes";
+
+// Binary decisions are 0 (lie) and 1 (truth), or this when the stimulus ended before the participant picked a side
+pub const VALUE_UNDECIDED: &'static str = "undecided";
+
+/// One row of the tidy table. Metadata events have no timestamp and only samples have a velocity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TidyRow {
+    pub participant: usize,
+    pub trial: Option<usize>,
+    pub screen: String,
+    pub event: String,
+    pub timestamp: Option<u128>,
+    pub value: String,
+    pub velocity: Option<f32>,
+}
+
+fn escape(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn optional<T: ToString>(field: &Option<T>) -> String {
+    match field {
+        Some(f) => f.to_string(),
+        None => String::default(),
+    }
+}
+
+/// Splits a CSV line, honouring double quoted fields
+fn split_line(line: &str) -> Vec<String> {
+    let mut fields: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            },
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+
+    fields.push(current);
+    fields
+}
+
+impl TidyRow {
+    pub fn to_csv(&self) -> String {
+        format!("{},{},{},{},{},{},{}",
+            self.participant,
+            optional(&self.trial),
+            escape(&self.screen),
+            escape(&self.event),
+            optional(&self.timestamp),
+            escape(&self.value),
+            optional(&self.velocity))
+    }
+
+    pub fn from_csv(line: &str) -> Option<TidyRow> {
+        let fields = split_line(line);
+
+        if fields.len() != 7 {
+            return None;
+        }
+
+        Some(TidyRow {
+            participant: fields[0].parse().ok()?,
+            trial: fields[1].parse().ok(),
+            screen: fields[2].clone(),
+            event: fields[3].clone(),
+            timestamp: fields[4].parse().ok(),
+            value: fields[5].clone(),
+            velocity: fields[6].parse().ok(),
+        })
+    }
+
+    /// Reads every row of a tidy file, skipping the header
+    pub fn read_all(contents: &str) -> Vec<TidyRow> {
+        contents.lines()
+            .skip(1)
+            .filter_map(TidyRow::from_csv)
+            .collect()
+    }
+}
@@ -0,0 +1,119 @@
+use std::fs::{create_dir_all, read_dir, read_to_string, rename, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::data::schema::{TidyRow, PARTICIPANT_FILE, STUDY_FILE, TIDY_HEADERS};
+use crate::views::ExperimentData;
+
+/// Keeps one tidy long-format table per participant (`<output>/<id>/session_long.csv`) and the
+/// study-wide aggregate (`<output>/study_long.csv`) up to date as screens are completed.
+/// A screen written again (e.g. after back navigation) replaces its earlier rows.
+pub struct TidyWriter {
+    output: PathBuf,
+    id: usize,
+    screens: Vec<(String, Vec<TidyRow>)>,
+}
+
+/// Writes to a temporary file first and renames it over the destination, so readers never see a partial table
+fn write_atomic(path: &Path, rows: &[TidyRow]) {
+    let temp_path = path.with_extension("csv.tmp");
+    let mut file = File::create(&temp_path).expect("Could not open tidy data file for writing");
+
+    file.write_all(TIDY_HEADERS.as_bytes()).expect("Could not write CSV header to file");
+    file.write_all("\n".as_bytes()).expect("Could not write to file");
+
+    for row in rows.iter() {
+        file.write_all(row.to_csv().as_bytes()).expect("Could not write CSV data");
+        file.write_all("\n".as_bytes()).expect("Could not write to file");
+    }
+
+    file.sync_all().expect("Could not flush the file to the disk");
+    rename(&temp_path, path).expect("Could not replace the tidy data file");
+}
+
+impl TidyWriter {
+    /// Starts an empty table for a new session
+    pub fn new(output: &Path, id: usize) -> TidyWriter {
+        TidyWriter {
+            output: output.to_path_buf(),
+            id,
+            screens: Vec::new()
+        }
+    }
+
+    /// Continues the table of a resumed session
+    pub fn resume(output: &Path, id: usize) -> TidyWriter {
+        let mut writer = TidyWriter::new(output, id);
+
+        if let Ok(contents) = read_to_string(writer.file_name()) {
+            for row in TidyRow::read_all(contents.as_str()) {
+                match writer.screens.iter_mut().find(|(name, _)| *name == row.screen) {
+                    Some((_, rows)) => rows.push(row),
+                    None => writer.screens.push((row.screen.clone(), vec![row])),
+                }
+            }
+        }
+
+        writer
+    }
+
+    fn file_name(&self) -> PathBuf {
+        self.output.join(self.id.to_string()).join(PARTICIPANT_FILE)
+    }
+
+    pub fn record(&mut self, experiment_data: Box<&dyn ExperimentData>) {
+        let screen = experiment_data.name();
+        let rows: Vec<TidyRow> = experiment_data.records()
+            .into_iter()
+            .map(|r| TidyRow {
+                participant: self.id,
+                trial: r.trial,
+                screen: screen.clone(),
+                event: r.event,
+                timestamp: r.timestamp,
+                value: r.value,
+                velocity: r.velocity,
+            })
+            .collect();
+
+        match self.screens.iter_mut().find(|(name, _)| *name == screen) {
+            Some((_, existing)) => *existing = rows,
+            None => self.screens.push((screen, rows)),
+        }
+
+        create_dir_all(self.output.join(self.id.to_string())).expect("Could not create the participant output folder");
+
+        let all_rows: Vec<TidyRow> = self.screens.iter().flat_map(|(_, rows)| rows.iter().cloned()).collect();
+        write_atomic(&self.file_name(), &all_rows);
+
+        update_study_file(&self.output);
+    }
+}
+
+/// Rebuilds the study-wide table from every participant's table
+pub fn update_study_file(output: &Path) {
+    let mut participants: Vec<(usize, String)> = Vec::new();
+
+    for entry in read_dir(output).expect("Could not read the output folder") {
+        let path = entry.expect("Could not read the output folder").path();
+
+        let id = match path.file_name().and_then(|n| n.to_str()).and_then(|n| n.parse::<usize>().ok()) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        let participant_file = path.join(PARTICIPANT_FILE);
+
+        if participant_file.exists() {
+            participants.push((id, read_to_string(participant_file).expect("Could not read a participant's tidy data")));
+        }
+    }
+
+    participants.sort_by_key(|(id, _)| *id);
+
+    let rows: Vec<TidyRow> = participants.iter()
+        .flat_map(|(_, contents)| TidyRow::read_all(contents.as_str()))
+        .collect();
+
+    write_atomic(&output.join(STUDY_FILE), &rows);
+}
@@ -0,0 +1,24 @@
+use surface_dial_rs::events::TopLevelEvent;
+use surface_dial_rs::SurfaceDial;
+
+use crate::input::DialInput;
+
+pub fn new_surface_dial<'a>() -> SurfaceDial<'a> {
+    let mut dial = SurfaceDial::new();
+    dial.set_subdivisions(60);
+    dial
+}
+
+impl DialInput for SurfaceDial<'_> {
+    fn pop_event(&mut self) -> Option<TopLevelEvent> {
+        SurfaceDial::pop_event(self)
+    }
+
+    fn set_subdivisions(&mut self, divisions: u16) {
+        SurfaceDial::set_subdivisions(self, divisions);
+    }
+
+    fn disable_subdivisions(&mut self) {
+        SurfaceDial::disable_subdivisions(self);
+    }
+}
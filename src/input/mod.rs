@@ -0,0 +1,49 @@
+use iced::keyboard::KeyCode;
+use surface_dial_rs::events::TopLevelEvent;
+use yaml_rust::Yaml;
+
+use crate::input::pump::DialPump;
+use crate::input::simulated::SimulatedDial;
+
+/// Source of dial events for the program. This is the Surface Dial during data collection,
+/// but can be swapped for a simulated dial on machines without one.
+pub trait DialInput {
+    fn pop_event(&mut self) -> Option<TopLevelEvent>;
+
+    fn set_subdivisions(&mut self, divisions: u16);
+
+    fn disable_subdivisions(&mut self);
+
+    /// Offers a key press or release to the input source
+    fn key_input(&mut self, _key: KeyCode, _pressed: bool) {}
+
+    /// Offers mouse wheel movement, in lines, to the input source
+    fn wheel_input(&mut self, _lines: f32) {}
+}
+
+/// Reads `config: input`, which is `dial` (the default), `simulated` or `pointer`, and starts polling it
+pub fn from_config(yaml_config: &Yaml) -> DialPump {
+    let input_type = if !yaml_config["config"]["input"].is_badvalue() {
+        yaml_config["config"]["input"].as_str().expect("Could not read the input type from config")
+    } else {
+        "dial"
+    };
+
+    match input_type {
+        "dial" => DialPump::start(false, || Box::new(hardware::new_surface_dial())),
+        "simulated" => {
+            println!("Using the simulated dial: arrow keys or the mouse wheel rotate, space presses the button");
+            DialPump::start(true, || Box::new(SimulatedDial::new()))
+        },
+        "pointer" => {
+            println!("Using the mouse or touchscreen: drag the arc to set the value, click its centre to press the button");
+            // The simulated dial never gets any input, it only stands in for the dial
+            DialPump::start(false, || Box::new(SimulatedDial::new())).with_pointer()
+        },
+        _ => panic!("Unknown input type '{}' in config", input_type),
+    }
+}
+
+pub mod hardware;
+pub mod pump;
+pub mod simulated;
@@ -0,0 +1,132 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use flume::{Receiver, Sender, TryRecvError};
+use iced::keyboard::KeyCode;
+use surface_dial_rs::events::TopLevelEvent;
+
+use crate::input::DialInput;
+use crate::input::simulated::SimulatedDial;
+
+/// How often the input thread checks the dial for new events
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// A dial event and the moment it was taken off the dial
+#[derive(Debug, Clone)]
+pub struct StampedEvent {
+    pub event: TopLevelEvent,
+    pub at: Instant,
+}
+
+enum DialCommand {
+    SetSubdivisions(u16),
+    DisableSubdivisions,
+    Key(KeyCode, bool),
+    Wheel(f32),
+}
+
+/// Owns the dial input on its own thread, which drains every event as soon as it is available and
+/// stamps it on arrival. The UI receives them in order through `events()`, independently of its redraw tick.
+pub struct DialPump {
+    commands: Sender<DialCommand>,
+    events: Receiver<StampedEvent>,
+    // Set when the keyboard and mouse wheel drive a simulated dial
+    keyboard: bool,
+    // Set when the arcs take mouse and touch input instead of the dial
+    pointer: bool,
+}
+
+impl DialPump {
+    /// The input is created on the polling thread, since the Surface Dial can't be moved between threads
+    pub fn start<F>(keyboard: bool, create_input: F) -> DialPump
+    where
+        F: FnOnce() -> Box<dyn DialInput> + Send + 'static
+    {
+        let (command_sender, command_receiver) = flume::unbounded::<DialCommand>();
+        let (event_sender, event_receiver) = flume::unbounded::<StampedEvent>();
+
+        thread::Builder::new()
+            .name("dial input".to_string())
+            .spawn(move || {
+                let mut input = create_input();
+
+                loop {
+                    loop {
+                        match command_receiver.try_recv() {
+                            Ok(DialCommand::SetSubdivisions(divisions)) => input.set_subdivisions(divisions),
+                            Ok(DialCommand::DisableSubdivisions) => input.disable_subdivisions(),
+                            Ok(DialCommand::Key(key, pressed)) => input.key_input(key, pressed),
+                            Ok(DialCommand::Wheel(lines)) => input.wheel_input(lines),
+                            Err(TryRecvError::Empty) => break,
+                            // The program has shut down
+                            Err(TryRecvError::Disconnected) => return,
+                        }
+                    }
+
+                    while let Some(event) = input.pop_event() {
+                        let stamped = StampedEvent {
+                            event,
+                            at: Instant::now(),
+                        };
+
+                        if event_sender.send(stamped).is_err() {
+                            return;
+                        }
+                    }
+
+                    thread::sleep(POLL_INTERVAL);
+                }
+            })
+            .expect("Could not start the dial input thread");
+
+        DialPump {
+            commands: command_sender,
+            events: event_receiver,
+            keyboard,
+            pointer: false,
+        }
+    }
+
+    /// Lets the arcs take mouse and touch input
+    pub fn with_pointer(mut self) -> DialPump {
+        self.pointer = true;
+        self
+    }
+
+    pub fn pointer_input(&self) -> bool {
+        self.pointer
+    }
+
+    /// The stamped events in arrival order. Every clone shares the same queue.
+    pub fn events(&self) -> Receiver<StampedEvent> {
+        self.events.clone()
+    }
+
+    pub fn set_subdivisions(&self, divisions: u16) {
+        self.send(DialCommand::SetSubdivisions(divisions));
+    }
+
+    pub fn disable_subdivisions(&self) {
+        self.send(DialCommand::DisableSubdivisions);
+    }
+
+    /// Passes the key on to a simulated dial. Returns true if the key was used as dial input.
+    pub fn key_input(&self, key: KeyCode, pressed: bool) -> bool {
+        if self.keyboard && SimulatedDial::uses_key(key) {
+            self.send(DialCommand::Key(key, pressed));
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn wheel_input(&self, lines: f32) {
+        if self.keyboard {
+            self.send(DialCommand::Wheel(lines));
+        }
+    }
+
+    fn send(&self, command: DialCommand) {
+        self.commands.send(command).expect("The dial input thread stopped");
+    }
+}
@@ -0,0 +1,84 @@
+use flume::{Receiver, Sender};
+use iced::keyboard::KeyCode;
+use surface_dial_rs::events::{DialDirection, DialEvent, TopLevelEvent};
+
+use crate::input::DialInput;
+
+/// Produces dial events from the keyboard and mouse so a session can be run without a Surface Dial.
+/// Right/Up and Left/Down rotate clockwise and counterclockwise, space is the dial button
+/// and every line of mouse wheel movement is one rotation step.
+pub struct SimulatedDial {
+    sender: Sender<TopLevelEvent>,
+    receiver: Receiver<TopLevelEvent>,
+    // Held keys repeat their press events, but the button should only be pressed once
+    button_down: bool,
+    // Wheel movement smaller than one line, kept until it adds up to a step
+    wheel_remainder: f32,
+}
+
+impl SimulatedDial {
+    pub fn new() -> SimulatedDial {
+        let (sender, receiver) = flume::unbounded();
+
+        SimulatedDial {
+            sender,
+            receiver,
+            button_down: false,
+            wheel_remainder: 0.0,
+        }
+    }
+
+    /// Whether the key drives the simulated dial rather than its usual shortcut
+    pub fn uses_key(key: KeyCode) -> bool {
+        matches!(key, KeyCode::Right | KeyCode::Up | KeyCode::Left | KeyCode::Down | KeyCode::Space)
+    }
+
+    fn rotate(&self, direction: DialDirection) {
+        self.push(TopLevelEvent::DialEvent(DialEvent::Rotate {
+            direction,
+            velocity: 1.0,
+        }));
+    }
+
+    fn push(&self, event: TopLevelEvent) {
+        self.sender.send(event).expect("The simulated dial queue was closed");
+    }
+}
+
+impl DialInput for SimulatedDial {
+    fn pop_event(&mut self) -> Option<TopLevelEvent> {
+        self.receiver.try_recv().ok()
+    }
+
+    // A simulated step is always one value, so subdivisions have no effect
+    fn set_subdivisions(&mut self, _divisions: u16) {}
+
+    fn disable_subdivisions(&mut self) {}
+
+    fn key_input(&mut self, key: KeyCode, pressed: bool) {
+        match key {
+            KeyCode::Right | KeyCode::Up if pressed => self.rotate(DialDirection::Clockwise),
+            KeyCode::Left | KeyCode::Down if pressed => self.rotate(DialDirection::Counterclockwise),
+            KeyCode::Space if pressed != self.button_down => {
+                self.button_down = pressed;
+                self.push(TopLevelEvent::DialEvent(DialEvent::Button { pressed }));
+            },
+            _ => {},
+        }
+    }
+
+    fn wheel_input(&mut self, lines: f32) {
+        self.wheel_remainder += lines;
+
+        // Scrolling up turns the dial clockwise
+        while self.wheel_remainder >= 1.0 {
+            self.rotate(DialDirection::Clockwise);
+            self.wheel_remainder -= 1.0;
+        }
+
+        while self.wheel_remainder <= -1.0 {
+            self.rotate(DialDirection::Counterclockwise);
+            self.wheel_remainder += 1.0;
+        }
+    }
+}
@@ -1,10 +1,9 @@
+use std::env;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
 use std::time::Duration;
-use rand::Rng;
-use rand::rngs::ThreadRng;
-use rand::{thread_rng, seq::IteratorRandom};
-
-use surface_dial_rs::SurfaceDial;
+use rand_chacha::ChaCha8Rng;
 
 extern crate iced;
 extern crate surface_dial_rs;
@@ -12,91 +11,337 @@ extern crate yaml_rust;
 
 use native_dialog::{MessageDialog, MessageType};
 
-use iced::{executor, time, window, Application, Command, Element, Settings, Subscription, Theme};
+use iced::{executor, time, window, Alignment, Application, Command, Element, Length, Settings, Subscription, Theme};
+use iced::widget::{Column, Text};
 use iced::keyboard::{self, KeyCode};
-use views::image_view::ImageView;
+use iced::mouse::{self, ScrollDelta};
 use views::participant_id_view::ParticipantIdView;
-use views::video_view::VideoView;
+use surface_dial_rs::events::{DialEvent, TopLevelEvent};
 use yaml_rust::{YamlLoader, Yaml};
 
 pub mod arc_input;
+mod clock;
+mod connection;
 mod data;
+mod input;
+mod phase;
+mod protocol;
+mod randomization;
+mod replay;
+mod stimuli;
+mod trials;
 mod views;
 
-use crate::views::arc_dichotomous_view::ArcDichotomousView;
-use crate::views::arc_input_video_view::ArcInputVideoView;
-use crate::views::info_view::InfoView;
-use crate::views::multichoice_view::MultiChoiceView;
-use crate::views::textinput_view::{TextInputType, TextInputView};
 use crate::views::ScreenCommand;
 
+use crate::arc_input::{ArcStyle, PointerEvent};
+use crate::clock::ReplayClock;
+use crate::connection::{ConnectionMonitor, ReconnectPolicy};
+
 use crate::data::write_data_file;
+use crate::data::schema::OUTPUT_FOLDER;
+use crate::data::journal::SessionJournal;
 use crate::data::partipant_data::ParticipantData;
-use crate::views::arc_question_scale::ArcQuestionScaleView;
-
-const VIDEO_NAMES: [&'static str; 2] = [
-    "alibi1_control.webm", // Lie
-    "alibi2_control.webm"  // Truth
-];
-
-enum AppState {
-    Participant,
-    Consent,
-    Instructions,
-    Videos,
-    Demographics,
-    Final
-}
-
-struct DynBaseProgram<'a> {
+use crate::data::tidy::TidyWriter;
+use crate::input::pump::{DialPump, StampedEvent};
+use crate::input::simulated::SimulatedDial;
+use crate::phase::{Phase, PhasePosition};
+use crate::protocol::Protocol;
+use crate::randomization::ParticipantSeed;
+use crate::replay::{ReplayAction, ReplayScript};
+use crate::stimuli::StimulusManifest;
+use crate::trials::{Trial, TrialOrderStrategy};
+
+struct DynBaseProgram {
     scaling_override: f64,
     config: Yaml,
-    valid_ids: Vec<u32>,
-    video_distributions: Vec<usize>,
-    num_vids: usize,
-    app_state: AppState,
-    dial: SurfaceDial<'a>,
-    current_screen: usize,
+    // Where the data files are written, next to the config
+    output: PathBuf,
+    manifest: StimulusManifest,
+    trial_order: Box<dyn TrialOrderStrategy>,
+    dial: DialPump,
+    connection: ConnectionMonitor,
     participant_data: Option<ParticipantData>,
+    protocol: Protocol,
+    arc_style: ArcStyle,
     participant_screen: Box<dyn views::DialView>,
-    consent_screens: Vec<Box<dyn views::DialView>>,
-    instruction_screen: usize,
-    all_instruction_screens: Vec<Box<dyn views::DialView>>,
-    screens: Vec<Box<dyn views::DialView>>,
-    demographics_screens: Vec<Box<dyn views::DialView>>,
-    final_screen: Box<dyn views::DialView>,
+    phases: Vec<Phase>,
+    // None while the participant ID is being entered
+    position: Option<PhasePosition>,
+    // Furthest screen reached, so screens revisited with back navigation keep their answers
+    furthest_position: Option<PhasePosition>,
+    journal: Option<SessionJournal>,
+    tidy: Option<TidyWriter>,
+    // Set when replaying a script without a window, so no dialogs are shown
+    headless: bool,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    ProcessDialEvents,
+    // Regular update for screens that change over time, e.g. videos ending
+    Tick,
+    DialEvent(StampedEvent),
     TextInputChanged(String),
     ButtonPressed,
+    BackPressed,
     RadioSelected(u32),
     KeyPressed(KeyCode),
-    KeyReleased(KeyCode)
+    KeyReleased(KeyCode),
+    WheelScrolled(f32),
+    // Mouse or touch input on an arc
+    Pointer(PointerEvent),
+    // A click or touch that no widget took
+    ScreenPressed,
 }
 
-impl DynBaseProgram<'_> {
+impl DynBaseProgram {
+    fn current_screen(&mut self) -> &mut Box<dyn views::DialView> {
+        match self.position {
+            Some(p) => &mut self.phases[p.phase].screens[p.screen],
+            None => &mut self.participant_screen,
+        }
+    }
+
+    fn enter_screen(&mut self, position: Option<PhasePosition>) {
+        self.position = position;
+        self.furthest_position = position;
+
+        if let Some(p) = position {
+            println!("Entering {} screen {}", self.phases[p.phase].name, p.screen);
+        }
+
+        let screen = self.current_screen();
+        screen.init();
+        screen.show();
+
+        let settings = screen.arc_settings();
+        self.update_dial_settings(settings);
+    }
+
+    /// Leaves the current screen, saves its data and moves to the next screen of the study.
+    /// Once the last phase is finished, the program returns to the participant ID screen.
+    fn next_screen(&mut self) {
+        let position = match self.position {
+            Some(p) => p,
+            None => return,
+        };
+
+        let screen = &mut self.phases[position.phase].screens[position.screen];
+        screen.hide();
+
+        // If this screen has data to write, export it
+        if let Some(experiment_data) = screen.data() {
+            write_data_file(&self.output, self.participant_data.as_ref().expect("Missing participant information").id, experiment_data);
+        }
+
+        // Raw dial events only go to their own file, they would swamp the tidy table
+        if let Some(raw_data) = screen.raw_data() {
+            write_data_file(&self.output, self.participant_data.as_ref().expect("Missing participant information").id, raw_data);
+        }
+
+        if let (Some(experiment_data), Some(tidy)) = (screen.data(), self.tidy.as_mut()) {
+            tidy.record(experiment_data);
+        }
+
+        if let Some(journal) = self.journal.as_ref() {
+            journal.record_completed(position, &self.phases[position.phase].name);
+        }
+
+        match position.next(&self.phases) {
+            Some(next) if self.furthest_position.map_or(false, |f| next <= f) => self.return_to_screen(next),
+            Some(next) => self.enter_screen(Some(next)),
+            None => {
+                if let Some(journal) = self.journal.take() {
+                    journal.record_finished();
+                }
+
+                self.tidy = None;
+
+                self.phases.clear();
+                self.participant_data = None;
+                self.enter_screen(None);
+            }
+        }
+    }
+
+    /// Returns to the previous screen if both this screen and the previous one allow it.
+    /// The previous screen is shown again without being reset so its earlier answer is kept.
+    fn previous_screen(&mut self) {
+        let position = match self.position {
+            Some(p) => p,
+            None => return,
+        };
+
+        let previous = match position.previous(&self.phases) {
+            Some(p) => p,
+            None => return,
+        };
+
+        if !self.phases[position.phase].screens[position.screen].allow_back() || !self.phases[previous.phase].screens[previous.screen].allow_back() {
+            return;
+        }
+
+        // The trials are done once their phase is left, even if its last screen could be revisited
+        if previous.phase != position.phase && self.phases[previous.phase].per_trial {
+            return;
+        }
+
+        self.phases[position.phase].screens[position.screen].hide();
+        self.return_to_screen(previous);
+    }
+
+    /// Shows a screen that was already visited without resetting it
+    fn return_to_screen(&mut self, position: PhasePosition) {
+        self.position = Some(position);
+
+        println!("Returning to {} screen {}", self.phases[position.phase].name, position.screen);
+
+        let screen = self.current_screen();
+        screen.show();
+
+        let settings = screen.arc_settings();
+        self.update_dial_settings(settings);
+    }
+
+    fn start_participant(&mut self, id: usize) {
+        println!("Preparing run for participant {}!", id);
+
+        self.journal = None;
+        self.tidy = None;
+
+        let info = self.config["participants"][id].clone();
+
+        if info.is_badvalue() {
+            if self.headless {
+                panic!("The participant ID {} does not have an entry in config", id);
+            }
+
+            // Tell the user that they selected an incorrect participant
+            MessageDialog::new()
+                .set_type(MessageType::Error)
+                .set_title("Invalid Participant")
+                .set_text(format!("The participant ID {} does not have an entry. Please select a different participant ID.", id).as_str())
+                .show_alert()
+                .unwrap();
+
+            self.enter_screen(None);
+            return;
+        }
+
+        let counterbalance = info["counterbalance"].as_bool().expect(format!("Participant {} is missing the counterbalance parameter", id).as_str());
+        let condition = info["condition"].as_str().expect(format!("Participant {} is missing the condition parameter", id).as_str()).to_string();
+
+        // Store the participant info and move on to the first phase
+        self.participant_data = Some(ParticipantData { 
+            id, 
+            data: info 
+        });
+
+        let resumable = SessionJournal::load(&self.output, id).filter(|j| !j.finished);
+
+        let resume = match resumable {
+            // Replays always start a fresh session so their output can be compared with the golden files
+            Some(_) if self.headless => None,
+            Some(journal) => {
+                let resume_confirmed = MessageDialog::new()
+                    .set_type(MessageType::Info)
+                    .set_title("Resume Session")
+                    .set_text(format!("Participant {} has an unfinished session. Do you want to resume it where it stopped?", id).as_str())
+                    .show_confirm()
+                    .unwrap();
+
+                if resume_confirmed { Some(journal) } else { None }
+            },
+            None => None,
+        };
+
+        let (counterbalance, condition, trials, resume_from) = match resume {
+            Some(journal) => {
+                println!("Resuming the session for participant {}", id);
+
+                let resume_from = journal.last_completed();
+                let trials: Vec<Trial> = journal.stimulus_ids.iter()
+                    .zip(journal.trial_uuids.iter())
+                    .enumerate()
+                    .map(|(position, (s, uuid))| {
+                        let stimulus = self.manifest.get(s).expect(format!("Stimulus {} from the session journal is not in the manifest", s).as_str());
+
+                        Trial {
+                            stimulus: stimulus.clone(),
+                            position,
+                            uuid: uuid.clone(),
+                        }
+                    })
+                    .collect();
+
+                self.journal = Some(SessionJournal::reopen(&self.output, id));
+                self.tidy = Some(TidyWriter::resume(&self.output, id));
+                (journal.counterbalance, journal.condition, trials, resume_from)
+            },
+            None => {
+                let seed = ParticipantSeed::from_config(&self.config, id);
+                println!("Randomizing trials with seed {}", seed.seed);
+                write_data_file(&self.output, id, Box::new(&seed));
+
+                let mut tidy = TidyWriter::new(&self.output, id);
+                tidy.record(Box::new(&seed));
+                self.tidy = Some(tidy);
+
+                let mut rng = seed.rng();
+                let trials = self.generate_trials(id, &mut rng);
+                self.journal = Some(SessionJournal::create(&self.output, id, seed.seed, counterbalance, &condition, &trials));
+                (counterbalance, condition, trials, None)
+            }
+        };
+
+        // The raw event logs of the session count from here, so their first event isn't always at zero
+        let session_start = clock::now();
+
+        if let Some(journal) = self.journal.as_ref() {
+            journal.record_session_start(clock::system_time());
+        }
+
+        // Build every phase of the protocol for this participant
+        self.phases = self.protocol.build(counterbalance, &condition, &trials, self.arc_style, session_start);
+
+        self.participant_screen.hide();
+
+        let first = match resume_from {
+            Some(completed) => completed.next(&self.phases),
+            None => PhasePosition::first_from(&self.phases, 0),
+        };
+
+        match first {
+            Some(first) => self.enter_screen(Some(first)),
+            None => panic!("The protocol has no screens left for the {} condition", condition),
+        }
+    }
+
+    fn generate_trials(&self, participant: usize, rng: &mut ChaCha8Rng) -> Vec<Trial> {
+        let mut trials = self.trial_order.generate(&self.manifest, participant, rng);
+        trials::assign_ids(&mut trials, rng);
+
+        for trial in trials.iter() {
+            println!("Video: {} ({})", trial.path(), trial.stimulus.label);
+        }
+
+        trials
+    }
+
     fn update_dial_settings(&mut self, settings: Option<views::ArcSettings>) {
         if let Some(actual_settings) = settings {
-            if actual_settings.divisions > 0 {
-                self.dial.set_subdivisions(actual_settings.divisions);
+            if actual_settings.active_divisions() > 0 {
+                self.dial.set_subdivisions(actual_settings.active_divisions());
             } else {
                 self.dial.disable_subdivisions();
             }
         }
     }
-}
-
-impl Application for DynBaseProgram<'_> {
-    type Executor = executor::Default;
-    type Message = Message;
-    type Flags = ();
-    type Theme = Theme;
 
-    fn new(_flags: ()) -> (Self, Command<Message>) {
-        let yaml_string = fs::read_to_string("config.yaml").expect("Could not load config file");
+    /// Loads `config.yaml` and the files it points to from `root`, and writes the data next to them.
+    /// The dial is set up from the config unless one is given.
+    fn create(root: &Path, dial: Option<DialPump>) -> DynBaseProgram {
+        let yaml_string = fs::read_to_string(root.join("config.yaml")).expect("Could not load config file");
         let yaml_docs = YamlLoader::load_from_str(yaml_string.as_str()).expect("Invalid YAML in config.yaml");
 
         let yaml_config = &yaml_docs[0];
@@ -107,151 +352,48 @@ impl Application for DynBaseProgram<'_> {
             0.0
         };
 
-        let valid_ids: Vec<u32> = if !yaml_config["videos"]["ids"].is_badvalue() {
-            let yaml_ids = yaml_config["videos"]["ids"].as_vec().expect("Could not read video ids from config");
-            let mut ids: Vec<u32> = Vec::new();
+        let manifest = StimulusManifest::from_config(yaml_config, root);
+        let trial_order = trials::from_config(yaml_config, root);
 
-            for id in yaml_ids.iter() {
-                ids.push(id.as_i64().expect("Could not read video id") as u32);
-            }
+        let dial = dial.unwrap_or_else(|| input::from_config(yaml_config));
+        let connection = ConnectionMonitor::new(ReconnectPolicy::from_config(yaml_config));
 
-            ids
-        } else {
-            Vec::new()
-        };
-
-        let num_vids: usize = if !yaml_config["videos"]["num"].is_badvalue() {
-            yaml_config["videos"]["num"].as_i64().expect("Could not read valid number of videos from config") as usize
-        } else {
-            4
-        };
-
-        let mut video_distributions = vec![ num_vids / 2, num_vids / 2 ];
-        let leftover_videos = num_vids % 2;
-        video_distributions[0] += leftover_videos;
-
-        let mut dial = SurfaceDial::new();
-
-        dial.set_subdivisions(60);
-
-        let consent_screens: Vec<Box<dyn views::DialView>> = vec![
-            Box::new(ImageView::new("Consent".to_string(), "images/consent-1.png".to_string())),
-            Box::new(ImageView::new("Consent".to_string(), "images/consent-2.png".to_string())),
-            Box::new(ImageView::new("Consent".to_string(), "images/consent-3.png".to_string())),
-            //Box::new(VideoView::new("videos/tutorial.webm".to_string())),
-        ];
+        let protocol = Protocol::load(yaml_config, root);
+        let arc_style = ArcStyle::from_config(yaml_config);
 
         let participant_screen: Box<dyn views::DialView> = Box::new(ParticipantIdView::new());
 
-        let all_instruction_screens: Vec<Box<dyn views::DialView>> = vec![
-            Box::new(ImageView::new("Instructions".to_string(), "images/dynamic-1.png".to_string())),
-            Box::new(ImageView::new("Instructions".to_string(), "images/dichotomous-1.png".to_string())),
-        ];
-
-        let instruction_screen: usize = 0;
-
-//         let instruction_screen: Box<dyn views::DialView> = Box::new(InfoView::new("Instructions".to_string(), 
-//         "In this study, you will watch video clips of different people providing an alibi, and answering
-// questions provided by an experimenter. Some people will be lying, whereas others will be telling
-// the truth. The clips will be randomly presented, so that each adult has a 50-50 likelihood of
-// telling the truth or lying. The segments are also independent. This means that if the person in
-// Clip 1 is telling the truth, there is still a 50-50 chance that the person in Clip 2 is telling the truth
-// or lying, and so on.\n\n
-// As you watch the video, please decide, as quickly and accurately as possible, whether the
-// person in the video was lying or telling the truth and use the dial to render your decision by
-// “locking in” your answer as demonstrated in the tutorial.".to_string()));
-
-        let mut screens: Vec<Box<dyn views::DialView>> = vec![];
-
-        let demographics_screens: Vec<Box<dyn views::DialView>> = vec![
-            Box::new(TextInputView::new(
-                TextInputType::Number, 
-                "demographics_age".to_string(), 
-                "Demographics".to_string(), 
-                "What is your current age?".to_string(),
-                "Age...".to_string())
-            ),
-            Box::new(MultiChoiceView::new(
-                "demographics_gender".to_string(), 
-                "Demographics".to_string(), 
-                "With which gender do you most identify (select one)?".to_string(), 
-                vec![
-                    (0, "Male".to_string()), 
-                    (1, "Female".to_string()), 
-                    (2, "Other".to_string()),
-                    (3, "Prefer not to disclose".to_string()),
-                ]
-            )),
-            Box::new(MultiChoiceView::new(
-                "demographics_race".to_string(), 
-                "Demographics".to_string(), 
-                "Which of the following races/ethnicities best describes you (select one)?".to_string(), 
-                vec![
-                    (0, "Aboriginal or indigenous (i.e., Alaskan native, American Indian, First Nations, Inuit, Metis)".to_string()), 
-                    (1, "Arab or West Asian (e.g., Armenian, Egyptian, Iranian, Lebanese, Moroccan)".to_string()), 
-                    (2, "Black (e.g., African, Haitian, Jamaican, Somali)".to_string()),
-                    (3, "Chinese".to_string()),
-                    (4, "Filipino".to_string()),
-                    (5, "Japanese".to_string()),
-                    (6, "Korean".to_string()),
-                    (7, "Latino/Hispanic".to_string()),
-                    (8, "Pacific Islander".to_string()),
-                    (9, "South Asian".to_string()),
-                    (10, "South East Asian".to_string()),
-                    (11, "White, non-Hispanic (i.e., Caucasian)".to_string()),
-                    (12, "Multi-ethnic".to_string()),
-                    (13, "Other".to_string()),
-                    (14, "Prefer not to disclose".to_string()),
-                ]
-            )),
-            Box::new(InfoView::new("Debriefing".to_string(), "As you read in the consent form, the goal of this study is to learn how people make decisions
-about deception. We are trying to find out whether the types of decision-making tool (i.e., the
-dial) that people use and the instructions that people receive when making lie detection decisions
-might affect judgments of deception. For example, if someone uses continuous dial judgments to
-make their decision, are they more likely to accurately judge if someone is a lie-teller than if they
-made a single dial choice after they viewed an account? Currently, the impact of the dial as a
-decision-making tool and instructions on lie detection are unknown.\n
-In this study, you watched videos of people who were being interviewed and provided an alibi
-for their whereabouts. We randomly chose which people would be encouraged to be honest, or
-lie, to the interviewer. As a result, people’s actions were primarily due to our experimental
-design and were not reflective of their natural behaviors or personal characteristics.\n
-Thank you so much for taking part in our study! I want to let you know that it is very important
-that you do not talk to anyone else about this study who hasn’t participated yet. If people know
-what we’re studying before they arrive, they might change their behavior, and then we wouldn’t
-be able to successfully run the study. So, it is very important that you do not discuss this study
-with others.\n
-If you wish to learn more about the study or the aggregate results, please feel free to contact the
-Principal Investigator, Elizabeth Elliott, at elliotte@iastate.edu\n
-Thank you again for participating!".to_string())),
-        ];
-
-        let final_screen: Box<dyn views::DialView> = Box::new(InfoView::new("Finished".to_string(), "Thank you for participating.".to_string()));
-
-        for s in screens.iter_mut() {
-            s.init();
+        DynBaseProgram {
+            scaling_override,
+            config: yaml_config.clone(),
+            output: root.join(OUTPUT_FOLDER),
+            manifest,
+            trial_order,
+            dial,
+            connection,
+            participant_data: None,
+            protocol,
+            arc_style,
+            participant_screen,
+            phases: Vec::new(),
+            position: None,
+            furthest_position: None,
+            journal: None,
+            tidy: None,
+            headless: false,
         }
+    }
+}
 
-        (
-            DynBaseProgram {
-                scaling_override,
-                config: yaml_config.clone(),
-                valid_ids,
-                video_distributions,
-                num_vids,
-                app_state: AppState::Participant,
-                dial,
-                current_screen: 0,
-                participant_data: None,
-                participant_screen,
-                consent_screens,
-                all_instruction_screens,
-                instruction_screen,
-                screens,
-                demographics_screens,
-                final_screen,
-            },
-            Command::none(),
-        )
+impl Application for DynBaseProgram {
+    type Executor = executor::Default;
+    type Message = Message;
+    // Dial input to use instead of the one set in config
+    type Flags = Option<DialPump>;
+    type Theme = Theme;
+
+    fn new(flags: Option<DialPump>) -> (Self, Command<Message>) {
+        (DynBaseProgram::create(Path::new("."), flags), Command::none())
     }
 
     fn title(&self) -> String {
@@ -260,225 +402,78 @@ Thank you again for participating!".to_string())),
 
     fn update(&mut self, message: Message) -> Command<Message> {
         let mut command = ScreenCommand::None;
-        let screen = match self.app_state {
-            AppState::Participant => &mut self.participant_screen,
-            AppState::Consent => &mut self.consent_screens[self.current_screen],
-            AppState::Instructions => &mut self.all_instruction_screens[self.instruction_screen],
-            AppState::Videos => &mut self.screens[self.current_screen],
-            AppState::Demographics => &mut self.demographics_screens[self.current_screen],
-            AppState::Final => &mut self.final_screen,
-        };
-        let dial = &self.dial;
 
         match message {
-            Message::ProcessDialEvents => {
-                let result = dial.pop_event();
-                command = screen.update(result);
-            }
+            Message::Tick => {
+                command = self.current_screen().update(None, clock::now());
+            },
+            Message::DialEvent(stamped) => {
+                if let Some(change) = self.connection.handle(&stamped.event, stamped.at) {
+                    self.current_screen().connection_changed(change);
+                }
+
+                command = self.current_screen().update(Some(stamped.event), stamped.at);
+            },
             Message::TextInputChanged(s) => {
                 command =
-                    screen.iced_input(Message::TextInputChanged(s));
+                    self.current_screen().iced_input(Message::TextInputChanged(s));
             }
             Message::ButtonPressed => {
-                command = screen.iced_input(Message::ButtonPressed);
+                command = self.current_screen().iced_input(Message::ButtonPressed);
             },
             Message::RadioSelected(c) => {
-                command = screen.iced_input(Message::RadioSelected(c));
+                command = self.current_screen().iced_input(Message::RadioSelected(c));
             }
+            Message::BackPressed => {
+                command = self.current_screen().iced_input(Message::BackPressed);
+            },
             Message::KeyPressed(k) => {
-                if k == KeyCode::Right {
-                    command = ScreenCommand::NextScreen(None);
+                // Navigation hotkeys are only available when the keys aren't simulating the dial,
+                // and not during an outage, since skipping a trial then would leave no trace of it
+                if !self.dial.key_input(k, true) && self.connection.is_connected() {
+                    if k == KeyCode::Right {
+                        command = ScreenCommand::NextScreen(None);
+                    } else if k == KeyCode::Left {
+                        command = ScreenCommand::PreviousScreen;
+                    }
+                }
+            },
+            Message::KeyReleased(k) => {
+                self.dial.key_input(k, false);
+            },
+            Message::WheelScrolled(lines) => {
+                self.dial.wheel_input(lines);
+            },
+            Message::Pointer(pointer) => {
+                // The arcs only respond to the mouse and touch when they replace the dial, and not during an outage
+                if self.dial.pointer_input() && self.connection.is_connected() {
+                    command = self.current_screen().iced_input(Message::Pointer(pointer));
+                }
+            },
+            Message::ScreenPressed => {
+                // Screens without an arc are advanced with the dial button, which the pointer stands in for
+                if self.dial.pointer_input() && self.connection.is_connected() && !self.current_screen().takes_pointer() {
+                    let press = TopLevelEvent::DialEvent(DialEvent::Button { pressed: true });
+                    command = self.current_screen().update(Some(press), clock::now());
                 }
             },
-            Message::KeyReleased(_) => {},
         }
 
         match command {
             ScreenCommand::NextScreen(c) => {
-                match self.app_state {
-                    AppState::Participant => {
-                        // Get participant from list and store it as participant data
-                        if let Some(config) = c {
-                            if config.contains_key("id") {
-                                let id = config["id"].parse::<usize>().unwrap();
-                                println!("Preparing run for participant {}!", id);
-
-                                let info = self.config["participants"][id].clone();
-
-                                if info.is_badvalue() {
-                                    // Tell the user that they selected an incorrect participant
-                                    MessageDialog::new()
-                                        .set_type(MessageType::Error)
-                                        .set_title("Invalid Participant")
-                                        .set_text(format!("The participant ID {} does not have an entry. Please select a different participant ID.", id).as_str())
-                                        .show_alert()
-                                        .unwrap();
-                                    
-                                    self.app_state = AppState::Participant;
-
-                                    self.participant_screen.init();
-                                    self.participant_screen.show();
-                                } else {
-                                    let counterbalance = info["counterbalance"].as_bool().expect(format!("Participant {} is missing the counterbalance parameter", id).as_str());
-                                    let condition = info["condition"].as_str().expect(format!("Participant {} is missing the condition parameter", id).as_str()).to_string();
-                                    let allow_lockin: bool = condition == "dynamic";
-                                    
-                                    // Make sure to show the correct instructions
-                                    self.instruction_screen = 0;
-                                    if condition == "dichotomous" {
-                                        self.instruction_screen = 1;
-                                    }
-
-                                    // Store the participant info and move on to instructions
-                                    self.participant_data = Some(ParticipantData { 
-                                        id, 
-                                        data: info 
-                                    });
-
-                                    let mut video_set = Vec::from(self.valid_ids.clone());
-                                    let mut vid_dist = self.video_distributions.clone();
-                                    
-                                    // Create a new set of video screens
-                                    for i in 0..self.num_vids {
-                                        // Select a random video path
-                                        let index = (0..video_set.len()).choose(&mut thread_rng()).unwrap();
-
-                                        // Remove the path from the set so it cannot be picked again
-                                        let vid_index = video_set.swap_remove(index);
-
-                                        let mut rng: ThreadRng = thread_rng();
-                                        let mut lie_truth_ind: usize = rng.gen_range(0..=1);
-                                        
-                                        // If we've exhausted this specific bucket, pick the other one
-                                        if vid_dist[lie_truth_ind] <= 0 {
-                                            lie_truth_ind = (lie_truth_ind + 1) % 2;
-                                        }
-
-                                        vid_dist[lie_truth_ind] -= 1;
-
-                                        let vid_name = VIDEO_NAMES[lie_truth_ind].clone();
-
-                                        let vid_path = format!("videos/{}/{}", vid_index, vid_name);
-
-                                        println!("Video: {}", vid_path);
-
-                                        self.screens.push(Box::new(ArcInputVideoView::new(i, vid_path, counterbalance, allow_lockin)));
-
-                                        if condition == "dichotomous" {
-                                            self.screens.push(Box::new(ArcDichotomousView::new(i, counterbalance)));
-                                        }
-
-                                        self.screens.push(Box::new(ArcQuestionScaleView::new(i, "confidence".to_string(), "How confident are you in your decision?".to_string(), "0".to_string(), "100".to_string(), 0, 100, 0)));
-                                        self.screens.push(Box::new(InfoView::new("Reminder".to_string(), "Remember, be as quick and accurate as possible. Please keep your hand on the dial at all times.".to_string())))
-                                    }
-
-                                    self.participant_screen.hide();
-
-                                    // Switch to the consent
-                                    self.app_state = AppState::Consent;
-
-                                    self.consent_screens[self.current_screen].init();
-                                    self.consent_screens[self.current_screen].show();
-
-                                    self.update_dial_settings(self.consent_screens[self.current_screen].arc_settings());
-                                }
-                            }
-                        }
-                    },
-                    AppState::Consent => {
-                        if self.current_screen + 1 < self.consent_screens.len() {                
-                            self.current_screen += 1;
-        
-                            self.consent_screens[self.current_screen].init();
-                            self.consent_screens[self.current_screen].show();
-        
-                            self.update_dial_settings(self.consent_screens[self.current_screen].arc_settings());
-                        } else if self.current_screen + 1 >= self.consent_screens.len() {
-                            self.current_screen = 0;
-
-                            // Switch to the instructions
-                            self.app_state = AppState::Instructions;
-
-                            self.all_instruction_screens[self.instruction_screen].init();
-                            self.all_instruction_screens[self.instruction_screen].show();
-
-                            self.update_dial_settings(self.all_instruction_screens[self.instruction_screen].arc_settings());
-                        }
-                        
-                    },
-                    AppState::Instructions => {
-                        self.all_instruction_screens[self.instruction_screen].hide();
-
-                        self.app_state = AppState::Videos;
-
-                        self.screens[0].init();
-                        self.screens[0].show();
-
-                        self.update_dial_settings(self.screens[0].arc_settings());
-                    },
-                    AppState::Videos => {
-                        self.screens[self.current_screen].hide();
-
-                        // If this screen has data to write, export it
-                        if let Some(experiment_data) = self.screens[self.current_screen].data() {
-                            write_data_file(self.participant_data.as_ref().expect("Missing participant information").id, experiment_data);
-                        }
-
-                        if self.current_screen + 1 < self.screens.len() {                
-                            self.current_screen += 1;
-        
-                            self.screens[self.current_screen].init();
-                            self.screens[self.current_screen].show();
-        
-                            self.update_dial_settings(self.screens[self.current_screen].arc_settings());
-                        } else if self.current_screen + 1 >= self.screens.len() {
-                            self.current_screen = 0;
-                            self.app_state = AppState::Demographics;
-
-                            self.demographics_screens[0].init();
-                            self.demographics_screens[0].show();
-
-                            self.update_dial_settings(self.demographics_screens[0].arc_settings());
-                        }
-                    },
-                    AppState::Demographics => {
-                        if self.current_screen + 1 < self.demographics_screens.len() {
-                            self.demographics_screens[self.current_screen].hide();
-        
-                            // If this screen has data to write, export it
-                            if let Some(experiment_data) = self.demographics_screens[self.current_screen].data() {
-                                write_data_file(self.participant_data.as_ref().expect("Missing participant information").id, experiment_data);
-                            }
-        
-                            self.current_screen += 1;
-        
-                            self.demographics_screens[self.current_screen].init();
-                            self.demographics_screens[self.current_screen].show();
-        
-                            self.update_dial_settings(self.demographics_screens[self.current_screen].arc_settings());
-                        } else if self.current_screen + 1 >= self.demographics_screens.len() {
-                            self.current_screen = 0;
-                            self.app_state = AppState::Final;
-
-                            self.final_screen.init();
-                            self.final_screen.show();
-
-                            self.update_dial_settings(self.final_screen.arc_settings());
-                        }                        
-                    },
-                    AppState::Final => {
-                        self.current_screen = 0;
-                        self.screens.clear();
-                        self.app_state = AppState::Participant;
-
-                        self.participant_screen.init();
-                        self.participant_screen.show();
-
-                        self.update_dial_settings(self.participant_screen.arc_settings());
+                if self.position.is_some() {
+                    self.next_screen();
+                } else if let Some(config) = c {
+                    // Get participant from list and store it as participant data
+                    if config.contains_key("id") {
+                        let id = config["id"].parse::<usize>().unwrap();
+                        self.start_participant(id);
                     }
                 }
             }
-            ScreenCommand::PreviousScreen => {}
+            ScreenCommand::PreviousScreen => {
+                self.previous_screen();
+            }
             _ => {}
         }
 
@@ -486,12 +481,18 @@ Thank you again for participating!".to_string())),
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        use iced_native::event::Event;
+        use iced_native::event::{Event, Status};
+        use iced_native::touch;
 
         Subscription::batch(vec![
             time::every(Duration::from_millis(1000 / 60 as u64))
-                .map(|_instant| Message::ProcessDialEvents),
-            iced_native::subscription::events_with(|event, _status| {
+                .map(|_instant| Message::Tick),
+            // Dial events are delivered as soon as they arrive rather than on the tick
+            iced::subscription::unfold("dial events", self.dial.events(), |events| async move {
+                let stamped = events.recv_async().await.expect("The dial input thread stopped");
+                (Some(Message::DialEvent(stamped)), events)
+            }),
+            iced_native::subscription::events_with(|event, status| {
                 match event {
                 Event::Keyboard(e) => {
                     match e {
@@ -504,6 +505,19 @@ Thank you again for participating!".to_string())),
                     _ => None
                     }
                 },
+                Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                    match delta {
+                        ScrollDelta::Lines { x: _, y } => Some(Message::WheelScrolled(y)),
+                        // Touchpads scroll in pixels, roughly 20 of which make up a line
+                        ScrollDelta::Pixels { x: _, y } => Some(Message::WheelScrolled(y / 20.0)),
+                    }
+                },
+                Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) if status == Status::Ignored => {
+                    Some(Message::ScreenPressed)
+                },
+                Event::Touch(touch::Event::FingerPressed { .. }) if status == Status::Ignored => {
+                    Some(Message::ScreenPressed)
+                },
                 _ => None,
                 }
             })
@@ -511,13 +525,21 @@ Thank you again for participating!".to_string())),
     }
 
     fn view(&self) -> Element<Message> {
-        match self.app_state {
-            AppState::Participant => return self.participant_screen.view(),
-            AppState::Consent => return self.consent_screens[self.current_screen].view(),
-            AppState::Instructions => return self.all_instruction_screens[self.instruction_screen].view(),
-            AppState::Videos => return self.screens[self.current_screen].view(),
-            AppState::Demographics => return self.demographics_screens[self.current_screen].view(),
-            AppState::Final => return self.final_screen.view(),
+        // The screen is paused until the dial is back, so the experimenter needs to step in
+        if !self.connection.is_connected() && self.position.is_some() {
+            return Column::new()
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .padding(40)
+                .align_items(Alignment::Center)
+                .push(Text::new("The dial has disconnected").size(30))
+                .push(Text::new("Please ask the experimenter to reconnect it. The study will continue once it is connected again.").size(18))
+                .into();
+        }
+
+        match self.position {
+            Some(p) => self.phases[p.phase].screens[p.screen].view(),
+            None => self.participant_screen.view(),
         }
     }
 
@@ -534,6 +556,110 @@ Thank you again for participating!".to_string())),
     }
 }
 
+/// How long a replay keeps ticking after its last step for the session to finish, in script time
+const REPLAY_GRACE: Duration = Duration::from_secs(10);
+
+/// Time between two ticks, the same as the rate of the tick subscription
+const TICK: Duration = Duration::from_millis(1000 / 60);
+
+/// Runs a scripted session through `update` without opening a window, with the config in `root` and the output
+/// written next to it. The clock follows the script and ticks at the rate of the subscription, without waiting in between.
+/// Returns the ID of the participant whose session was replayed.
+fn run_replay(root: &Path, script: ReplayScript) -> usize {
+    // Dial events come straight from the script, so the simulated dial only takes the subdivision settings
+    let mut program = DynBaseProgram::create(root, Some(DialPump::start(false, || Box::new(SimulatedDial::new()))));
+    program.headless = true;
+
+    let clock = ReplayClock::start();
+    let last_step = script.steps.last().map_or(Duration::ZERO, |s| s.at);
+    let mut steps = script.steps.into_iter().peekable();
+    let mut participant: Option<usize> = None;
+    let mut time = Duration::ZERO;
+
+    loop {
+        while let Some(step) = steps.next_if(|s| s.at <= time) {
+            clock.set(step.at);
+
+            match step.action {
+                ReplayAction::Dial(event) => { program.update(Message::DialEvent(StampedEvent { event, at: clock::now() })); },
+                ReplayAction::Program(message) => { program.update(message); },
+            }
+        }
+
+        clock.set(time);
+        program.update(Message::Tick);
+
+        if let Some(data) = program.participant_data.as_ref() {
+            participant = Some(data.id);
+        }
+
+        let finished = participant.is_some() && program.position.is_none();
+
+        if steps.peek().is_none() && finished {
+            break;
+        }
+
+        if time > last_step + REPLAY_GRACE {
+            eprintln!("The replay ended before the session finished, it stopped at {:?}", program.position);
+            process::exit(1);
+        }
+
+        time += TICK;
+    }
+
+    participant.expect("The replay never started a session")
+}
+
+/// `--replay <script>` runs a scripted session without a window, with the config in the working folder.
+/// `--golden <folder>` then compares the output with the files in the folder, or with `--update-golden` replaces them.
 pub fn main() -> iced::Result {
+    let args: Vec<String> = env::args().collect();
+    let option = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned();
+
+    if let Some(script) = option("--replay") {
+        let root = Path::new(".");
+        let id = run_replay(root, ReplayScript::load(Path::new(script.as_str())));
+
+        if let Some(golden) = option("--golden") {
+            if args.iter().any(|a| a == "--update-golden") {
+                replay::update_golden(Path::new(golden.as_str()), &root.join(OUTPUT_FOLDER), id);
+                println!("Updated the golden files in {}", golden);
+            } else {
+                let differences = replay::compare_golden(Path::new(golden.as_str()), &root.join(OUTPUT_FOLDER), id);
+
+                for difference in differences.iter() {
+                    eprintln!("{}", difference);
+                }
+
+                if !differences.is_empty() {
+                    process::exit(1);
+                }
+
+                println!("The output matches the golden files in {}", golden);
+            }
+        }
+
+        return Ok(());
+    }
+
     DynBaseProgram::run(Settings::default())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replays `replay/sample/session.txt` against the config next to it and compares the output with `replay/sample/golden`
+    #[test]
+    fn sample_session_matches_golden() {
+        let root = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/replay/sample"));
+        let output = root.join(OUTPUT_FOLDER);
+        // Output of earlier runs would be archived as revisions instead of replaced
+        let _ = fs::remove_dir_all(&output);
+
+        let id = run_replay(root, ReplayScript::load(&root.join("session.txt")));
+        let differences = replay::compare_golden(&root.join("golden"), &output, id);
+
+        assert!(differences.is_empty(), "{}", differences.join("\n"));
+    }
+}
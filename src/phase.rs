@@ -0,0 +1,63 @@
+use crate::views::DialView;
+
+/// A named block of screens in the study (e.g. consent, a practice block or the video trials)
+pub struct Phase {
+    pub name: String,
+    pub screens: Vec<Box<dyn DialView>>,
+    /// Whether the screens are repeated for every trial
+    pub per_trial: bool,
+}
+
+impl Phase {
+    pub fn new(name: String, screens: Vec<Box<dyn DialView>>, per_trial: bool) -> Phase {
+        Phase {
+            name,
+            screens,
+            per_trial
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.screens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.screens.is_empty()
+    }
+}
+
+/// Position of the participant within the list of phases
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PhasePosition {
+    pub phase: usize,
+    pub screen: usize,
+}
+
+impl PhasePosition {
+    /// The first screen at or after the given phase, skipping phases without screens
+    pub fn first_from(phases: &[Phase], phase: usize) -> Option<PhasePosition> {
+        (phase..phases.len())
+            .find(|p| !phases[*p].is_empty())
+            .map(|p| PhasePosition { phase: p, screen: 0 })
+    }
+
+    pub fn next(&self, phases: &[Phase]) -> Option<PhasePosition> {
+        if self.screen + 1 < phases[self.phase].len() {
+            Some(PhasePosition { phase: self.phase, screen: self.screen + 1 })
+        } else {
+            PhasePosition::first_from(phases, self.phase + 1)
+        }
+    }
+
+    /// The screen before this one, stepping back into earlier phases when needed
+    pub fn previous(&self, phases: &[Phase]) -> Option<PhasePosition> {
+        if self.screen > 0 {
+            return Some(PhasePosition { phase: self.phase, screen: self.screen - 1 });
+        }
+
+        (0..self.phase)
+            .rev()
+            .find(|p| !phases[*p].is_empty())
+            .map(|p| PhasePosition { phase: p, screen: phases[p].len() - 1 })
+    }
+}
@@ -0,0 +1,455 @@
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use yaml_rust::{Yaml, YamlLoader};
+
+use crate::arc_input::{ArcStyle, DisplayMode};
+use crate::data::schema::{SCREEN_DICHOTOMOUS, SCREEN_DYNAMIC};
+use crate::phase::Phase;
+use crate::trials::Trial;
+use crate::views::{ArcSettings, DialView};
+use crate::views::continuous_rating_view::{ContinuousRatingView, RatingSpec, ResponseScale, Stimulus};
+use crate::views::image_view::ImageView;
+use crate::views::info_view::InfoView;
+use crate::views::lock_in::{LockInPolicy, LockInRules};
+use crate::views::multichoice_view::MultiChoiceView;
+use crate::views::mapping::ValueMapping;
+use crate::views::recording::RecordingPolicy;
+use crate::views::textinput_view::{TextInputType, TextInputView};
+use crate::views::video_view::VideoView;
+
+const DEFAULT_PROTOCOL_FILE: &'static str = "protocol.yaml";
+
+// Detents of the default haptic profiles
+const ARC_VIDEO_DIVISIONS: u16 = 60;
+const DICHOTOMOUS_DIVISIONS: u16 = 10;
+const ARC_SCALE_DIVISIONS: u16 = 80;
+
+/// The stimulus of a rating screen. Videos and audio without a path play the trial's video.
+pub enum StimulusSource {
+    None,
+    Image(String),
+    Video(Option<String>),
+    Audio(Option<String>),
+}
+
+impl StimulusSource {
+    /// Reads a screen's `stimulus` entry, e.g. `{ type: image, path: "images/face.png" }` or `{ type: audio }`
+    pub fn from_yaml(yaml: &Yaml) -> StimulusSource {
+        if yaml.is_badvalue() {
+            return StimulusSource::None;
+        }
+
+        match yaml["type"].as_str().expect("The stimulus is missing its type") {
+            "none" => StimulusSource::None,
+            "image" => StimulusSource::Image(read_string(yaml, "path", "image stimulus")),
+            "video" => StimulusSource::Video(read_optional_string(yaml, "path")),
+            "audio" => StimulusSource::Audio(read_optional_string(yaml, "path")),
+            s => panic!("Unknown stimulus type '{}' in the protocol", s),
+        }
+    }
+
+    fn is_media(&self) -> bool {
+        matches!(self, StimulusSource::Video(_) | StimulusSource::Audio(_))
+    }
+}
+
+pub enum ScreenKind {
+    Image { title: String, path: String },
+    Info { title: String, message: String },
+    TextInput { input_type: String, name: String, title: String, label: String, hint: String },
+    MultiChoice { name: String, title: String, message: String, choices: Vec<(u32, String)> },
+    Video { path: Option<String> },
+    /// The `arc_video`, `dichotomous` and `arc_scale` screens are presets of this one.
+    /// `counterbalance` mirrors the scale for counterbalanced participants.
+    Rating {
+        name: String,
+        title: String,
+        footer: Option<String>,
+        stimulus: StimulusSource,
+        scale: ResponseScale,
+        lock_in: LockInPolicy,
+        left_label: String,
+        right_label: String,
+        counterbalance: bool,
+        record_velocity: bool,
+        divisions: u16,
+    },
+}
+
+pub struct ScreenSpec {
+    pub kind: ScreenKind,
+    pub conditions: Vec<String>,
+    /// When dynamic decisions are stored, only used by the rating screens
+    pub recording: RecordingPolicy,
+    /// The screen's `haptics` entry, applied on top of the rating screen's default detents
+    pub haptics: Yaml,
+    /// How rotations change the value, only used by rating screens with a continuous scale
+    pub mapping: ValueMapping,
+    /// Extra labels along the arc as (value, label), only used by the rating screens
+    pub anchors: Vec<(i32, String)>,
+    /// How much of the value the arc shows, only used by the rating screens
+    pub display: DisplayMode,
+    /// Minimum viewing time, confirmation, revision window and change limit, only used by the rating screens
+    pub lock_in_rules: LockInRules,
+}
+
+pub struct PhaseSpec {
+    pub name: String,
+    pub repeat_per_trial: bool,
+    pub screens: Vec<ScreenSpec>,
+}
+
+pub struct Protocol {
+    pub phases: Vec<PhaseSpec>,
+}
+
+/// Everything a screen needs to know about the participant and the trial it belongs to
+pub struct ScreenContext<'a> {
+    pub index: usize,
+    pub counterbalance: bool,
+    pub condition: &'a str,
+    pub trial: Option<&'a Trial>,
+    pub arc_style: ArcStyle,
+    pub session_start: Instant,
+}
+
+fn read_string(yaml: &Yaml, key: &str, screen_type: &str) -> String {
+    yaml[key].as_str().expect(format!("The {} screen is missing the {} parameter", screen_type, key).as_str()).to_string()
+}
+
+fn read_string_or(yaml: &Yaml, key: &str, default: &str) -> String {
+    if !yaml[key].is_badvalue() {
+        yaml[key].as_str().expect(format!("Could not read the {} parameter", key).as_str()).to_string()
+    } else {
+        default.to_string()
+    }
+}
+
+pub(crate) fn read_i32_or(yaml: &Yaml, key: &str, default: i32) -> i32 {
+    if !yaml[key].is_badvalue() {
+        yaml[key].as_i64().expect(format!("Could not read the {} parameter", key).as_str()) as i32
+    } else {
+        default
+    }
+}
+
+fn read_optional_string(yaml: &Yaml, key: &str) -> Option<String> {
+    yaml[key].as_str().map(|s| s.to_string())
+}
+
+impl ScreenSpec {
+    pub fn from_yaml(yaml: &Yaml) -> ScreenSpec {
+        let screen_type = yaml["type"].as_str().expect("A screen in the protocol is missing its type");
+
+        let kind = match screen_type {
+            "image" => ScreenKind::Image {
+                title: read_string_or(yaml, "title", ""),
+                path: read_string(yaml, "path", screen_type),
+            },
+            "info" => ScreenKind::Info {
+                title: read_string_or(yaml, "title", ""),
+                message: read_string(yaml, "message", screen_type),
+            },
+            "text_input" => ScreenKind::TextInput {
+                input_type: read_string_or(yaml, "input", "all"),
+                name: read_string(yaml, "name", screen_type),
+                title: read_string_or(yaml, "title", ""),
+                label: read_string(yaml, "label", screen_type),
+                hint: read_string_or(yaml, "hint", ""),
+            },
+            "multichoice" => {
+                let yaml_choices = yaml["choices"].as_vec().expect("The multichoice screen is missing the choices parameter");
+                let mut choices: Vec<(u32, String)> = Vec::new();
+
+                for (i, choice) in yaml_choices.iter().enumerate() {
+                    choices.push((i as u32, choice.as_str().expect("Could not read multichoice choice").to_string()));
+                }
+
+                ScreenKind::MultiChoice {
+                    name: read_string(yaml, "name", screen_type),
+                    title: read_string_or(yaml, "title", ""),
+                    message: read_string(yaml, "message", screen_type),
+                    choices,
+                }
+            },
+            "arc_scale" => {
+                let min = read_i32_or(yaml, "min", 0);
+                let max = read_i32_or(yaml, "max", 100);
+                let anchor = read_i32_or(yaml, "anchor", min);
+                let initial = read_i32_or(yaml, "initial", anchor);
+
+                if max <= min || anchor < min || anchor > max || initial < min || initial > max {
+                    panic!("The arc_scale screen needs min < max and an initial value and anchor between them");
+                }
+
+                ScreenKind::Rating {
+                    name: read_string(yaml, "name", screen_type),
+                    title: read_string(yaml, "question", screen_type),
+                    footer: None,
+                    stimulus: StimulusSource::None,
+                    scale: ResponseScale::Continuous { min, max, initial, anchor },
+                    lock_in: LockInPolicy::Button,
+                    left_label: read_string_or(yaml, "left_label", ""),
+                    right_label: read_string_or(yaml, "right_label", ""),
+                    counterbalance: false,
+                    record_velocity: false,
+                    divisions: ARC_SCALE_DIVISIONS,
+                }
+            },
+            "video" => ScreenKind::Video {
+                path: read_optional_string(yaml, "path"),
+            },
+            "arc_video" => ScreenKind::Rating {
+                name: SCREEN_DYNAMIC.to_string(),
+                title: "Is the person lying or telling the truth?".to_string(),
+                footer: Some("Your last decision will be made final at the end of the video".to_string()),
+                stimulus: StimulusSource::Video(read_optional_string(yaml, "path")),
+                scale: ResponseScale::Continuous { min: -10, max: 10, initial: 0, anchor: 0 },
+                lock_in: if !yaml["lock_in"].is_badvalue() {
+                    LockInPolicy::from_yaml(&yaml["lock_in"])
+                } else if yaml["allow_lockin"].as_bool().unwrap_or(true) {
+                    LockInPolicy::Either
+                } else {
+                    LockInPolicy::EndOfStimulus
+                },
+                left_label: "Lie".to_string(),
+                right_label: "Truth".to_string(),
+                counterbalance: true,
+                record_velocity: true,
+                divisions: ARC_VIDEO_DIVISIONS,
+            },
+            "dichotomous" => ScreenKind::Rating {
+                name: SCREEN_DICHOTOMOUS.to_string(),
+                title: "Was the person lying or telling the truth?".to_string(),
+                footer: None,
+                stimulus: StimulusSource::None,
+                scale: ResponseScale::Binary,
+                lock_in: LockInPolicy::Button,
+                left_label: "Lie".to_string(),
+                right_label: "Truth".to_string(),
+                counterbalance: true,
+                record_velocity: false,
+                divisions: DICHOTOMOUS_DIVISIONS,
+            },
+            "rating" => {
+                let stimulus = StimulusSource::from_yaml(&yaml["stimulus"]);
+                let scale = ResponseScale::from_yaml(&yaml["scale"]);
+                let lock_in = LockInPolicy::from_yaml(&yaml["lock_in"]);
+
+                if lock_in != LockInPolicy::Button && !stimulus.is_media() {
+                    panic!("Only rating screens with a video or audio stimulus can lock in at its end");
+                }
+
+                ScreenKind::Rating {
+                    name: read_string(yaml, "name", screen_type),
+                    title: read_string_or(yaml, "title", ""),
+                    footer: read_optional_string(yaml, "footer"),
+                    stimulus,
+                    scale,
+                    lock_in,
+                    left_label: read_string_or(yaml, "left_label", ""),
+                    right_label: read_string_or(yaml, "right_label", ""),
+                    counterbalance: yaml["counterbalance"].as_bool().unwrap_or(false),
+                    record_velocity: yaml["velocity"].as_bool().unwrap_or(false),
+                    divisions: if scale == ResponseScale::Binary { DICHOTOMOUS_DIVISIONS } else { ARC_VIDEO_DIVISIONS },
+                }
+            },
+            _ => panic!("Unknown screen type '{}' in the protocol", screen_type),
+        };
+
+        let mut conditions: Vec<String> = Vec::new();
+
+        if let Some(yaml_conditions) = yaml["conditions"].as_vec() {
+            for c in yaml_conditions.iter() {
+                conditions.push(c.as_str().expect("Could not read screen condition").to_string());
+            }
+        }
+
+        let mut anchors: Vec<(i32, String)> = Vec::new();
+
+        if let Some(yaml_anchors) = yaml["anchors"].as_vec() {
+            for a in yaml_anchors.iter() {
+                let value = a["value"].as_i64().expect("An anchor in the protocol is missing its value") as i32;
+                anchors.push((value, read_string(a, "label", "anchor")));
+            }
+        }
+
+        ScreenSpec {
+            kind,
+            conditions,
+            recording: RecordingPolicy::from_yaml(&yaml["recording"]),
+            haptics: yaml["haptics"].clone(),
+            mapping: ValueMapping::from_yaml(&yaml["mapping"]),
+            anchors,
+            display: DisplayMode::from_yaml(&yaml["display"]),
+            lock_in_rules: LockInRules::from_yaml(&yaml["lock_in_rules"]),
+        }
+    }
+
+    /// Screens without any conditions are shown to every participant
+    pub fn applies_to(&self, condition: &str) -> bool {
+        self.conditions.is_empty() || self.conditions.iter().any(|c| c == condition)
+    }
+
+    pub fn build(&self, context: &ScreenContext) -> Box<dyn DialView> {
+        let video_path = |path: &Option<String>| -> String {
+            match path {
+                Some(p) => p.clone(),
+                None => context.trial.expect("A video screen has no path and is not part of a trial phase").path(),
+            }
+        };
+
+        let trial = context.trial.map(|t| t.metadata(context.condition));
+        let haptics = |divisions: u16| ArcSettings::detents(divisions).with_yaml(&self.haptics);
+
+        match &self.kind {
+            ScreenKind::Image { title, path } => Box::new(ImageView::new(title.clone(), path.clone())),
+            ScreenKind::Info { title, message } => Box::new(InfoView::new(title.clone(), message.clone())),
+            ScreenKind::TextInput { input_type, name, title, label, hint } => {
+                let input_type = match input_type.as_str() {
+                    "alphanumeric" => TextInputType::Alphanumeric,
+                    "number" => TextInputType::Number,
+                    "characters" => TextInputType::Characters,
+                    "all" => TextInputType::All,
+                    _ => panic!("Unknown text input type '{}' in the protocol", input_type),
+                };
+
+                Box::new(TextInputView::new(input_type, name.clone(), title.clone(), label.clone(), hint.clone()))
+            },
+            ScreenKind::MultiChoice { name, title, message, choices } => {
+                Box::new(MultiChoiceView::new(name.clone(), title.clone(), message.clone(), choices.clone()))
+            },
+            ScreenKind::Video { path } => Box::new(VideoView::new(video_path(path))),
+            ScreenKind::Rating { name, title, footer, stimulus, scale, lock_in, left_label, right_label, counterbalance, record_velocity, divisions } => {
+                let stimulus = match stimulus {
+                    StimulusSource::None => Stimulus::None,
+                    StimulusSource::Image(path) => Stimulus::Image(path.clone()),
+                    StimulusSource::Video(path) => Stimulus::Video(video_path(path)),
+                    StimulusSource::Audio(path) => Stimulus::Audio(video_path(path)),
+                };
+
+                let spec = RatingSpec {
+                    name: name.clone(),
+                    title: title.clone(),
+                    footer: footer.clone(),
+                    stimulus,
+                    scale: *scale,
+                    lock_in: *lock_in,
+                    lock_in_rules: self.lock_in_rules,
+                    left_label: left_label.clone(),
+                    right_label: right_label.clone(),
+                    anchors: self.anchors.clone(),
+                    counterbalance: if *counterbalance { Some(context.counterbalance) } else { None },
+                    record_velocity: *record_velocity,
+                    recording: self.recording,
+                    haptics: haptics(*divisions),
+                    mapping: self.mapping,
+                    display: self.display,
+                    style: context.arc_style,
+                    session_start: context.session_start,
+                };
+
+                Box::new(ContinuousRatingView::new(context.index, spec, trial))
+            },
+        }
+    }
+}
+
+impl PhaseSpec {
+    pub fn from_yaml(yaml: &Yaml) -> PhaseSpec {
+        let name = yaml["phase"].as_str().expect("A phase in the protocol is missing its name").to_string();
+        let repeat_per_trial = yaml["repeat"].as_str() == Some("trials");
+
+        let yaml_screens = yaml["screens"].as_vec().expect(format!("The {} phase has no screens", name).as_str());
+        let mut screens: Vec<ScreenSpec> = Vec::new();
+
+        for s in yaml_screens.iter() {
+            screens.push(ScreenSpec::from_yaml(s));
+        }
+
+        PhaseSpec {
+            name,
+            repeat_per_trial,
+            screens,
+        }
+    }
+
+    /// Creates the screens for one participant. Trial phases are repeated for every trial.
+    pub fn build(&self, counterbalance: bool, condition: &str, trials: &[Trial], arc_style: ArcStyle, session_start: Instant) -> Vec<Box<dyn DialView>> {
+        let mut screens: Vec<Box<dyn DialView>> = Vec::new();
+
+        if self.repeat_per_trial {
+            for (i, trial) in trials.iter().enumerate() {
+                let context = ScreenContext {
+                    index: i,
+                    counterbalance,
+                    condition,
+                    trial: Some(trial),
+                    arc_style,
+                    session_start,
+                };
+
+                for spec in self.screens.iter().filter(|s| s.applies_to(condition)) {
+                    screens.push(spec.build(&context));
+                }
+            }
+        } else {
+            for (i, spec) in self.screens.iter().filter(|s| s.applies_to(condition)).enumerate() {
+                let context = ScreenContext {
+                    index: i,
+                    counterbalance,
+                    condition,
+                    trial: None,
+                    arc_style,
+                    session_start,
+                };
+
+                screens.push(spec.build(&context));
+            }
+        }
+
+        screens
+    }
+}
+
+impl Protocol {
+    /// Reads the protocol from the `protocol` section of the config, or from the protocol file it points to relative to `root`
+    pub fn load(yaml_config: &Yaml, root: &Path) -> Protocol {
+        if yaml_config["protocol"].as_vec().is_some() {
+            return Protocol::from_yaml(&yaml_config["protocol"]);
+        }
+
+        let file_name = if !yaml_config["config"]["protocol"].is_badvalue() {
+            yaml_config["config"]["protocol"].as_str().expect("Could not read protocol file name from config").to_string()
+        } else {
+            DEFAULT_PROTOCOL_FILE.to_string()
+        };
+
+        let yaml_string = fs::read_to_string(root.join(file_name.as_str())).expect(format!("Could not load protocol file {}", file_name).as_str());
+        let yaml_docs = YamlLoader::load_from_str(yaml_string.as_str()).expect(format!("Invalid YAML in {}", file_name).as_str());
+
+        Protocol::from_yaml(&yaml_docs[0]["protocol"])
+    }
+
+    pub fn from_yaml(yaml: &Yaml) -> Protocol {
+        let yaml_phases = yaml.as_vec().expect("The protocol must be a list of phases");
+        let mut phases: Vec<PhaseSpec> = Vec::new();
+
+        for p in yaml_phases.iter() {
+            phases.push(PhaseSpec::from_yaml(p));
+        }
+
+        Protocol {
+            phases
+        }
+    }
+
+    /// Creates every phase of the study for one participant, in protocol order
+    pub fn build(&self, counterbalance: bool, condition: &str, trials: &[Trial], arc_style: ArcStyle, session_start: Instant) -> Vec<Phase> {
+        self.phases.iter()
+            .map(|p| Phase::new(p.name.clone(), p.build(counterbalance, condition, trials, arc_style, session_start), p.repeat_per_trial))
+            .collect()
+    }
+}
@@ -0,0 +1,95 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use yaml_rust::Yaml;
+
+use crate::views::{DataRecord, ExperimentData, Printable};
+
+/// Where a participant's seed came from, recorded so the trial order can be audited later
+pub enum SeedSource {
+    Participant,
+    Study,
+}
+
+impl SeedSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SeedSource::Participant => "participant",
+            SeedSource::Study => "study",
+        }
+    }
+}
+
+pub struct ParticipantSeed {
+    pub seed: u64,
+    pub study_seed: u64,
+    pub source: SeedSource,
+}
+
+/// SplitMix64 finalizer, used to spread participant IDs over the whole seed space
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+impl ParticipantSeed {
+    /// Uses the participant's `seed` entry if there is one, otherwise derives it from the study seed
+    /// (`config: seed`) and the participant ID so every run for the same participant is identical.
+    pub fn from_config(yaml_config: &Yaml, id: usize) -> ParticipantSeed {
+        let study_seed = if !yaml_config["config"]["seed"].is_badvalue() {
+            yaml_config["config"]["seed"].as_i64().expect("Could not read the study seed from config") as u64
+        } else {
+            0
+        };
+
+        let participant_seed = &yaml_config["participants"][id]["seed"];
+
+        if !participant_seed.is_badvalue() {
+            ParticipantSeed {
+                seed: participant_seed.as_i64().expect(format!("Could not read the seed of participant {}", id).as_str()) as u64,
+                study_seed,
+                source: SeedSource::Participant,
+            }
+        } else {
+            ParticipantSeed {
+                seed: mix(study_seed ^ mix(id as u64)),
+                study_seed,
+                source: SeedSource::Study,
+            }
+        }
+    }
+
+    /// ChaCha is used rather than `StdRng` because its output is guaranteed not to change between versions
+    pub fn rng(&self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.seed)
+    }
+}
+
+impl ExperimentData for ParticipantSeed {
+    fn name(&self) -> String {
+        "seed".to_string()
+    }
+
+    fn headers(&self) -> String {
+        "seed,study_seed,source".to_string()
+    }
+
+    fn data(&self) -> Box<&dyn Printable> {
+        Box::new(self)
+    }
+
+    fn records(&self) -> Vec<DataRecord> {
+        vec![
+            DataRecord::metadata(None, "seed", self.seed.to_string()),
+            DataRecord::metadata(None, "study_seed", self.study_seed.to_string()),
+            DataRecord::metadata(None, "seed_source", self.source.name().to_string()),
+        ]
+    }
+}
+
+impl Printable for ParticipantSeed {
+    fn to_csv(&self) -> String {
+        format!("{},{},{}\n", self.seed, self.study_seed, self.source.name())
+    }
+}
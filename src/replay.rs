@@ -0,0 +1,146 @@
+use std::fs::{self, copy, create_dir_all};
+use std::path::Path;
+use std::time::Duration;
+
+use surface_dial_rs::events::{ConnectionEvent, DialDirection, DialEvent, TopLevelEvent};
+
+use crate::Message;
+
+pub enum ReplayAction {
+    /// Delivered to the program as if it came from the dial
+    Dial(TopLevelEvent),
+    /// Sent to the program as if it came from the window, e.g. typing or clicking a button
+    Program(Message),
+}
+
+pub struct ReplayStep {
+    /// Time since the start of the replay
+    pub at: Duration,
+    pub action: ReplayAction,
+}
+
+/// A session written as one step per line: `<time> <action> [argument]`. Time is in milliseconds since the
+/// start of the replay, or since the previous step when written as `+<ms>`. Lines starting with `#` are comments.
+///
+/// Dial actions are `cw [count]`, `ccw [count]`, `press`, `release`, `connect` and `disconnect`.
+/// Window actions are `text <value>`, `radio <index>`, `button` and `back`.
+///
+/// ```text
+/// 0 text 3
+/// +100 button
+/// +1000 press
+/// +100 release
+/// +2000 cw 10
+/// ```
+pub struct ReplayScript {
+    pub steps: Vec<ReplayStep>,
+}
+
+impl ReplayScript {
+    pub fn load(path: &Path) -> ReplayScript {
+        let file_name = path.display();
+        let contents = fs::read_to_string(path).expect(format!("Could not load replay script {}", file_name).as_str());
+
+        let mut steps: Vec<ReplayStep> = Vec::new();
+        let mut last = Duration::ZERO;
+
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(3, ' ');
+            let time = parts.next().unwrap();
+            let action = parts.next().expect(format!("Line {} of {} has no action", number + 1, file_name).as_str());
+            let argument = parts.next().map(|a| a.trim());
+
+            let at = match time.strip_prefix('+') {
+                Some(relative) => last + Duration::from_millis(relative.parse::<u64>().expect(format!("Invalid time on line {} of {}", number + 1, file_name).as_str())),
+                None => Duration::from_millis(time.parse::<u64>().expect(format!("Invalid time on line {} of {}", number + 1, file_name).as_str())),
+            };
+            last = at;
+
+            let count = argument.map_or(1, |a| a.parse::<usize>().expect(format!("Invalid count on line {} of {}", number + 1, file_name).as_str()));
+
+            let actions: Vec<ReplayAction> = match action {
+                "cw" => (0..count).map(|_| rotate(DialDirection::Clockwise)).collect(),
+                "ccw" => (0..count).map(|_| rotate(DialDirection::Counterclockwise)).collect(),
+                "press" => vec![ReplayAction::Dial(TopLevelEvent::DialEvent(DialEvent::Button { pressed: true }))],
+                "release" => vec![ReplayAction::Dial(TopLevelEvent::DialEvent(DialEvent::Button { pressed: false }))],
+                "connect" => vec![ReplayAction::Dial(TopLevelEvent::ConnectionEvent(ConnectionEvent::Connected))],
+                "disconnect" => vec![ReplayAction::Dial(TopLevelEvent::ConnectionEvent(ConnectionEvent::Disconnected))],
+                "text" => vec![ReplayAction::Program(Message::TextInputChanged(argument.unwrap_or("").to_string()))],
+                "radio" => vec![ReplayAction::Program(Message::RadioSelected(count as u32))],
+                "button" => vec![ReplayAction::Program(Message::ButtonPressed)],
+                "back" => vec![ReplayAction::Program(Message::BackPressed)],
+                _ => panic!("Unknown action '{}' on line {} of {}", action, number + 1, file_name),
+            };
+
+            steps.extend(actions.into_iter().map(|action| ReplayStep { at, action }));
+        }
+
+        ReplayScript {
+            steps
+        }
+    }
+}
+
+fn rotate(direction: DialDirection) -> ReplayAction {
+    ReplayAction::Dial(TopLevelEvent::DialEvent(DialEvent::Rotate {
+        direction,
+        velocity: 1.0,
+    }))
+}
+
+/// Compares every file in the golden folder with the participant's files in the output folder, line by line.
+/// The replay clock follows the script, so the timing columns have to match as well.
+/// Returns a description of each difference.
+pub fn compare_golden(golden_folder: &Path, output: &Path, id: usize) -> Vec<String> {
+    let mut differences: Vec<String> = Vec::new();
+
+    for entry in fs::read_dir(golden_folder).expect(format!("Could not read golden folder {}", golden_folder.display()).as_str()) {
+        let golden_path = entry.expect("Could not read the golden folder").path();
+        let output_path = output.join(id.to_string()).join(golden_path.file_name().unwrap());
+
+        let output = match fs::read_to_string(&output_path) {
+            Ok(o) => o,
+            Err(_) => {
+                differences.push(format!("{} was not written", output_path.display()));
+                continue;
+            }
+        };
+
+        let golden = fs::read_to_string(&golden_path).expect("Could not read golden file");
+        let (golden_lines, output_lines): (Vec<&str>, Vec<&str>) = (golden.lines().collect(), output.lines().collect());
+
+        if golden_lines.len() != output_lines.len() {
+            differences.push(format!("{} has {} lines, expected {}", output_path.display(), output_lines.len(), golden_lines.len()));
+        }
+
+        for (line, (expected, actual)) in golden_lines.iter().zip(output_lines.iter()).enumerate() {
+            if expected != actual {
+                differences.push(format!("{} line {}: expected '{}', got '{}'", output_path.display(), line + 1, expected, actual));
+            }
+        }
+    }
+
+    differences
+}
+
+/// Copies the participant's data files into the golden folder, skipping revisions of rewritten files
+pub fn update_golden(golden_folder: &Path, output: &Path, id: usize) {
+    create_dir_all(golden_folder).expect("Could not create the golden folder");
+
+    for entry in fs::read_dir(output.join(id.to_string())).expect("Could not read the participant output folder") {
+        let path = entry.expect("Could not read the participant output folder").path();
+        let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+
+        if !file_name.ends_with(".csv") || file_name.contains(".rev") {
+            continue;
+        }
+
+        copy(&path, golden_folder.join(file_name)).expect("Could not copy a file into the golden folder");
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use yaml_rust::{Yaml, YamlLoader};
+
+/// Video layout used before stimulus manifests existed: `videos/<id>/alibi1_control.webm` is the
+/// lie and `videos/<id>/alibi2_control.webm` is the truth told by the same speaker
+const LEGACY_VIDEOS: [(&'static str, &'static str); 2] = [
+    ("alibi1_control.webm", "lie"),
+    ("alibi2_control.webm", "truth"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stimulus {
+    pub id: String,
+    pub path: String,
+    /// Ground truth, e.g. `lie` or `truth`
+    pub label: String,
+    pub speaker: String,
+    pub duration_ms: Option<u64>,
+    pub tags: HashMap<String, String>,
+}
+
+/// Characters that would break the `key=value;key=value` tag list or the CSV row it is written to
+const TAG_SEPARATORS: [char; 3] = [',', ';', '='];
+
+pub struct StimulusManifest {
+    pub stimuli: Vec<Stimulus>,
+}
+
+impl Stimulus {
+    fn from_yaml(yaml: &Yaml) -> Stimulus {
+        let id = yaml["id"].as_str().expect("A stimulus in the manifest is missing its id").to_string();
+
+        let mut tags: HashMap<String, String> = HashMap::new();
+
+        if let Some(yaml_tags) = yaml["tags"].as_hash() {
+            for (key, value) in yaml_tags.iter() {
+                let value = match value {
+                    Yaml::String(s) => s.clone(),
+                    Yaml::Integer(i) => i.to_string(),
+                    Yaml::Real(r) => r.clone(),
+                    Yaml::Boolean(b) => b.to_string(),
+                    _ => panic!("Tags of stimulus {} must be plain values", id),
+                };
+
+                let key = key.as_str().expect("Stimulus tag names must be strings").to_string();
+
+                if key.contains(TAG_SEPARATORS) || value.contains(TAG_SEPARATORS) {
+                    panic!("Tags of stimulus {} can't contain ',', ';' or '='", id);
+                }
+
+                tags.insert(key, value);
+            }
+        }
+
+        Stimulus {
+            path: yaml["path"].as_str().expect(format!("Stimulus {} is missing its path", id).as_str()).to_string(),
+            label: yaml["label"].as_str().expect(format!("Stimulus {} is missing its label", id).as_str()).to_string(),
+            speaker: match &yaml["speaker"] {
+                Yaml::Integer(i) => i.to_string(),
+                s => s.as_str().expect(format!("Stimulus {} is missing its speaker", id).as_str()).to_string(),
+            },
+            duration_ms: yaml["duration_ms"].as_i64().map(|d| d as u64),
+            tags,
+            id,
+        }
+    }
+
+    /// The tags in the `key=value;key=value` form of the CSV manifest, sorted by key
+    pub fn tags_text(&self) -> String {
+        let mut tags: Vec<String> = self.tags.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        tags.sort();
+        tags.join(";")
+    }
+
+    /// CSV columns are `id,path,label,speaker,duration_ms,tags` with tags written as `key=value;key=value`
+    fn from_csv(line: &str) -> Stimulus {
+        let columns: Vec<&str> = line.split(',').map(|c| c.trim()).collect();
+
+        if columns.len() < 4 {
+            panic!("Stimulus manifest line '{}' should have at least the columns id,path,label,speaker", line);
+        }
+
+        let mut tags: HashMap<String, String> = HashMap::new();
+
+        if let Some(tag_column) = columns.get(5) {
+            for tag in tag_column.split(';').filter(|t| !t.is_empty()) {
+                let (key, value) = tag.split_once('=').expect(format!("Stimulus tag '{}' should be written as key=value", tag).as_str());
+                tags.insert(key.to_string(), value.to_string());
+            }
+        }
+
+        Stimulus {
+            id: columns[0].to_string(),
+            path: columns[1].to_string(),
+            label: columns[2].to_string(),
+            speaker: columns[3].to_string(),
+            duration_ms: columns.get(4).and_then(|d| d.parse::<u64>().ok()),
+            tags,
+        }
+    }
+}
+
+impl StimulusManifest {
+    /// Loads the manifest set in `videos: manifest`, relative to `root`, or builds one from `videos: ids` using the
+    /// legacy layout. When both are given, only the speakers listed in `ids` are used.
+    pub fn from_config(yaml_config: &Yaml, root: &Path) -> StimulusManifest {
+        let videos = &yaml_config["videos"];
+
+        let ids: Option<Vec<String>> = videos["ids"].as_vec().map(|yaml_ids| {
+            yaml_ids.iter()
+                .map(|id| id.as_i64().expect("Could not read video id").to_string())
+                .collect()
+        });
+
+        let manifest = match videos["manifest"].as_str() {
+            Some(file_name) => StimulusManifest::load(&root.join(file_name)),
+            None => StimulusManifest::from_legacy_ids(ids.as_ref().expect("The config needs either videos: manifest or videos: ids")),
+        };
+
+        match ids {
+            Some(ids) => StimulusManifest {
+                stimuli: manifest.stimuli.into_iter().filter(|s| ids.contains(&s.speaker)).collect()
+            },
+            None => manifest,
+        }
+    }
+
+    pub fn load(path: &Path) -> StimulusManifest {
+        let file_name = path.display();
+        let contents = fs::read_to_string(path).expect(format!("Could not load stimulus manifest {}", file_name).as_str());
+
+        let stimuli: Vec<Stimulus> = if matches!(path.extension().and_then(|e| e.to_str()), Some("csv")) {
+            contents.lines()
+                .skip(1)
+                .filter(|l| !l.trim().is_empty())
+                .map(Stimulus::from_csv)
+                .collect()
+        } else {
+            let yaml_docs = YamlLoader::load_from_str(contents.as_str()).expect(format!("Invalid YAML in {}", file_name).as_str());
+
+            yaml_docs[0]["stimuli"].as_vec()
+                .expect(format!("{} has no stimuli list", file_name).as_str())
+                .iter()
+                .map(Stimulus::from_yaml)
+                .collect()
+        };
+
+        StimulusManifest {
+            stimuli
+        }
+    }
+
+    pub fn from_legacy_ids(ids: &[String]) -> StimulusManifest {
+        let mut stimuli: Vec<Stimulus> = Vec::new();
+
+        for id in ids.iter() {
+            for (file, label) in LEGACY_VIDEOS.iter() {
+                stimuli.push(Stimulus {
+                    id: format!("{}_{}", id, label),
+                    path: format!("videos/{}/{}", id, file),
+                    label: label.to_string(),
+                    speaker: id.clone(),
+                    duration_ms: None,
+                    tags: HashMap::new(),
+                });
+            }
+        }
+
+        StimulusManifest {
+            stimuli
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Stimulus> {
+        self.stimuli.iter().find(|s| s.id == id)
+    }
+
+    /// The stimulus in which `speaker` gives the `label` account
+    pub fn find(&self, speaker: &str, label: &str) -> Option<&Stimulus> {
+        self.stimuli.iter().find(|s| s.speaker == speaker && s.label == label)
+    }
+
+    /// Every ground truth label in the manifest, in the order they first appear
+    pub fn labels(&self) -> Vec<String> {
+        let mut labels: Vec<String> = Vec::new();
+
+        for s in self.stimuli.iter() {
+            if !labels.contains(&s.label) {
+                labels.push(s.label.clone());
+            }
+        }
+
+        labels
+    }
+
+    /// Every speaker in the manifest, in the order they first appear
+    pub fn speakers(&self) -> Vec<String> {
+        let mut speakers: Vec<String> = Vec::new();
+
+        for s in self.stimuli.iter() {
+            if !speakers.contains(&s.speaker) {
+                speakers.push(s.speaker.clone());
+            }
+        }
+
+        speakers
+    }
+}
@@ -0,0 +1,130 @@
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+
+use crate::stimuli::StimulusManifest;
+use crate::trials::{label_distribution, Trial, TrialOrderStrategy};
+
+/// Splits the trials into blocks of `block_size` that each contain the same number of trials of
+/// every ground truth label in a random order (e.g. 5 lie + 5 truth per block of 10)
+pub struct BlockedOrder {
+    num_vids: usize,
+    block_size: usize,
+}
+
+impl BlockedOrder {
+    pub fn new(num_vids: usize, block_size: usize) -> BlockedOrder {
+        if block_size == 0 {
+            panic!("The block size of the blocked trial design must be above 0");
+        }
+
+        BlockedOrder {
+            num_vids,
+            block_size
+        }
+    }
+}
+
+impl TrialOrderStrategy for BlockedOrder {
+    fn generate(&self, manifest: &StimulusManifest, _participant: usize, rng: &mut ChaCha8Rng) -> Vec<Trial> {
+        let labels = manifest.labels();
+        let mut speakers = manifest.speakers();
+        speakers.shuffle(rng);
+
+        let mut trials: Vec<Trial> = Vec::new();
+        let mut remaining = self.num_vids;
+        let mut block = 0;
+
+        while remaining > 0 {
+            let size = remaining.min(self.block_size);
+
+            // Blocks that cannot be split evenly rotate which labels get the extra trials
+            let mut dist = label_distribution(size, labels.len());
+            dist.rotate_right(block % labels.len());
+
+            let mut block_labels: Vec<usize> = Vec::new();
+            for (label, count) in dist.iter().enumerate() {
+                block_labels.extend(std::iter::repeat(label).take(*count));
+            }
+
+            block_labels.shuffle(rng);
+
+            for label in block_labels {
+                // Use the next unused speaker that has a stimulus with this label
+                let index = speakers.iter()
+                    .position(|s| manifest.find(s, &labels[label]).is_some())
+                    .expect("There are not enough speakers in the manifest for the number of videos");
+                let speaker = speakers.remove(index);
+
+                trials.push(Trial::new(manifest.find(&speaker, &labels[label]).unwrap()));
+            }
+
+            remaining -= size;
+            block += 1;
+        }
+
+        trials
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    const SEEDS: u64 = 200;
+
+    fn manifest(speakers: usize) -> StimulusManifest {
+        StimulusManifest::from_legacy_ids(&(1..=speakers).map(|i| i.to_string()).collect::<Vec<String>>())
+    }
+
+    fn count(block: &[Trial], label: &str) -> usize {
+        block.iter().filter(|t| t.stimulus.label == label).count()
+    }
+
+    #[test]
+    fn every_block_has_as_many_lies_as_truths() {
+        let manifest = manifest(20);
+        let order = BlockedOrder::new(20, 10);
+
+        for seed in 0..SEEDS {
+            let trials = order.generate(&manifest, 1, &mut ChaCha8Rng::seed_from_u64(seed));
+            assert_eq!(trials.len(), 20);
+
+            for block in trials.chunks(10) {
+                assert_eq!(count(block, "lie"), 5, "seed {}", seed);
+                assert_eq!(count(block, "truth"), 5, "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn uneven_blocks_take_turns_with_the_extra_trial() {
+        let manifest = manifest(15);
+        let order = BlockedOrder::new(15, 5);
+
+        for seed in 0..SEEDS {
+            let trials = order.generate(&manifest, 1, &mut ChaCha8Rng::seed_from_u64(seed));
+            let lies: Vec<usize> = trials.chunks(5).map(|b| count(b, "lie")).collect();
+
+            assert_eq!(lies, vec![3, 2, 3], "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn no_speaker_is_seen_twice() {
+        let manifest = manifest(12);
+        let order = BlockedOrder::new(12, 4);
+
+        for seed in 0..SEEDS {
+            let mut speakers: Vec<String> = order.generate(&manifest, 1, &mut ChaCha8Rng::seed_from_u64(seed))
+                .into_iter()
+                .map(|t| t.stimulus.speaker)
+                .collect();
+            speakers.sort();
+            speakers.dedup();
+
+            assert_eq!(speakers.len(), 12, "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use rand_chacha::ChaCha8Rng;
+
+use crate::stimuli::StimulusManifest;
+use crate::trials::{Trial, TrialOrderStrategy};
+
+/// Pre-specified trial orders read from a CSV file with the columns `participant,speaker,label`.
+/// Rows are used in file order. Rows with `*` as the participant are used for anyone without their own rows.
+/// Speakers and labels are matched exactly as they are written in the stimulus manifest.
+pub struct FixedOrder {
+    orders: HashMap<String, Vec<(String, String)>>,
+}
+
+impl FixedOrder {
+    pub fn load(path: &Path) -> FixedOrder {
+        let contents = fs::read_to_string(path).expect(format!("Could not load the trial order file {}", path.display()).as_str());
+
+        FixedOrder::parse(contents.as_str(), path.display().to_string().as_str())
+    }
+
+    /// Reads the rows of a trial order file, named `file_name` in errors
+    pub fn parse(contents: &str, file_name: &str) -> FixedOrder {
+        let mut orders: HashMap<String, Vec<(String, String)>> = HashMap::new();
+
+        for (line_number, line) in contents.lines().enumerate().skip(1) {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let columns: Vec<&str> = line.split(',').map(|c| c.trim()).collect();
+
+            if columns.len() < 3 {
+                panic!("Line {} of {} should have the columns participant,speaker,label", line_number + 1, file_name);
+            }
+
+            orders.entry(columns[0].to_string())
+                .or_insert_with(Vec::new)
+                .push((columns[1].to_string(), columns[2].to_string()));
+        }
+
+        FixedOrder {
+            orders
+        }
+    }
+}
+
+impl TrialOrderStrategy for FixedOrder {
+    fn generate(&self, manifest: &StimulusManifest, participant: usize, _rng: &mut ChaCha8Rng) -> Vec<Trial> {
+        self.orders.get(&participant.to_string())
+            .or_else(|| self.orders.get("*"))
+            .expect(format!("The trial order file has no order for participant {}", participant).as_str())
+            .iter()
+            .map(|(speaker, label)| {
+                let stimulus = manifest.find(speaker, label)
+                    .expect(format!("The manifest has no {} stimulus for speaker {}", label, speaker).as_str());
+
+                Trial::new(stimulus)
+            })
+            .collect()
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn manifest() -> StimulusManifest {
+        StimulusManifest::from_legacy_ids(&["6".to_string(), "7".to_string(), "8".to_string()])
+    }
+
+    fn order(fixed: &FixedOrder, participant: usize) -> Vec<String> {
+        fixed.generate(&manifest(), participant, &mut ChaCha8Rng::seed_from_u64(0))
+            .iter()
+            .map(|t| t.stimulus.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn participants_get_their_rows_in_file_order() {
+        let fixed = FixedOrder::parse("participant,speaker,label\n1,7,truth\n1, 6 , lie\n\n2,8,lie\n", "order.csv");
+
+        assert_eq!(order(&fixed, 1), vec!["7_truth", "6_lie"]);
+        assert_eq!(order(&fixed, 2), vec!["8_lie"]);
+    }
+
+    #[test]
+    fn participants_without_rows_get_the_shared_order() {
+        let fixed = FixedOrder::parse("participant,speaker,label\n*,8,truth\n*,6,truth\n1,7,lie\n", "order.csv");
+
+        assert_eq!(order(&fixed, 1), vec!["7_lie"]);
+        assert_eq!(order(&fixed, 5), vec!["8_truth", "6_truth"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Line 3 of order.csv should have the columns participant,speaker,label")]
+    fn rows_without_every_column_are_rejected() {
+        FixedOrder::parse("participant,speaker,label\n1,6,lie\n1,7\n", "order.csv");
+    }
+
+    #[test]
+    #[should_panic(expected = "The trial order file has no order for participant 2")]
+    fn participants_without_an_order_are_rejected() {
+        let fixed = FixedOrder::parse("participant,speaker,label\n1,6,lie\n", "order.csv");
+        order(&fixed, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "The manifest has no lie stimulus for speaker 9")]
+    fn stimuli_missing_from_the_manifest_are_rejected() {
+        let fixed = FixedOrder::parse("participant,speaker,label\n1,9,lie\n", "order.csv");
+        order(&fixed, 1);
+    }
+}
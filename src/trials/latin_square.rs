@@ -0,0 +1,161 @@
+use rand_chacha::ChaCha8Rng;
+
+use crate::stimuli::StimulusManifest;
+use crate::trials::{Trial, TrialOrderStrategy};
+
+/// Every participant sees the first `num` speakers of the manifest, with the order taken from a row of a
+/// balanced (Williams) Latin square. Participant 1 gets the first row, participant 2 the second and so on.
+/// The ground truth labels cycle through the speakers and shift along with the row, so every speaker
+/// is seen with every label across participants.
+pub struct LatinSquareOrder {
+    num_vids: usize,
+}
+
+impl LatinSquareOrder {
+    pub fn new(num_vids: usize) -> LatinSquareOrder {
+        LatinSquareOrder {
+            num_vids
+        }
+    }
+
+    /// Williams design: the first row is 0, n-1, 1, n-2, 2, ... and every other row is shifted by one.
+    /// Squares with an odd number of items need the mirrored rows as well to be balanced.
+    fn row(&self, row: usize) -> Vec<usize> {
+        let n = self.num_vids;
+        let mut first_row: Vec<usize> = Vec::new();
+        let (mut low, mut high) = (0, n);
+
+        for i in 0..n {
+            if i % 2 == 0 {
+                first_row.push(low);
+                low += 1;
+            } else {
+                high -= 1;
+                first_row.push(high);
+            }
+        }
+
+        let rows = if n % 2 == 0 { n } else { 2 * n };
+        let row = row % rows;
+
+        let mut order: Vec<usize> = first_row.iter().map(|i| (i + row) % n).collect();
+
+        if row >= n {
+            order.reverse();
+        }
+
+        order
+    }
+}
+
+impl TrialOrderStrategy for LatinSquareOrder {
+    fn generate(&self, manifest: &StimulusManifest, participant: usize, _rng: &mut ChaCha8Rng) -> Vec<Trial> {
+        let speakers = manifest.speakers();
+        let labels = manifest.labels();
+
+        if self.num_vids > speakers.len() {
+            panic!("The Latin square design needs at least {} speakers in the manifest", self.num_vids);
+        }
+
+        let row = participant.saturating_sub(1);
+
+        self.row(row)
+            .iter()
+            .map(|i| {
+                let label = &labels[(i + row) % labels.len()];
+                let stimulus = manifest.find(&speakers[*i], label)
+                    .expect(format!("Speaker {} has no {} stimulus", speakers[*i], label).as_str());
+
+                Trial::new(stimulus)
+            })
+            .collect()
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rand::SeedableRng;
+
+    use super::*;
+
+    /// Every row of the design, including the mirrored rows of odd squares
+    fn square(n: usize) -> Vec<Vec<usize>> {
+        let order = LatinSquareOrder::new(n);
+        let rows = if n % 2 == 0 { n } else { 2 * n };
+
+        (0..rows).map(|r| order.row(r)).collect()
+    }
+
+    #[test]
+    fn every_item_is_once_in_each_row_and_position() {
+        for n in 2..=9 {
+            let square = square(n);
+
+            for row in square.iter() {
+                let mut sorted = row.clone();
+                sorted.sort();
+                assert_eq!(sorted, (0..n).collect::<Vec<usize>>(), "n = {}", n);
+            }
+
+            // Odd squares are two Latin squares on top of each other, so every item is twice in each position
+            let per_position = square.len() / n;
+
+            for position in 0..n {
+                for item in 0..n {
+                    assert_eq!(square.iter().filter(|r| r[position] == item).count(), per_position, "n = {}, position {}", n, position);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_item_follows_every_other_item_equally_often() {
+        for n in 2..=9 {
+            let mut pairs: HashMap<(usize, usize), usize> = HashMap::new();
+
+            for row in square(n).iter() {
+                for pair in row.windows(2) {
+                    *pairs.entry((pair[0], pair[1])).or_insert(0) += 1;
+                }
+            }
+
+            let expected = if n % 2 == 0 { 1 } else { 2 };
+
+            assert_eq!(pairs.len(), n * (n - 1), "n = {}", n);
+            assert!(pairs.values().all(|c| *c == expected), "n = {}: {:?}", n, pairs);
+        }
+    }
+
+    #[test]
+    fn rows_repeat_after_the_last_one() {
+        let order = LatinSquareOrder::new(4);
+        assert_eq!(order.row(1), order.row(5));
+
+        let order = LatinSquareOrder::new(3);
+        assert_eq!(order.row(4), order.row(10));
+    }
+
+    #[test]
+    fn speakers_see_every_label_across_participants() {
+        let ids: Vec<String> = (1..=4).map(|i| i.to_string()).collect();
+        let manifest = StimulusManifest::from_legacy_ids(&ids);
+        let order = LatinSquareOrder::new(4);
+
+        for speaker in ids.iter() {
+            let labels: Vec<String> = (1..=4)
+                .flat_map(|participant| order.generate(&manifest, participant, &mut ChaCha8Rng::seed_from_u64(0)))
+                .filter(|t| t.stimulus.speaker == *speaker)
+                .map(|t| t.stimulus.label)
+                .collect();
+
+            assert_eq!(labels.iter().filter(|l| *l == "lie").count(), 2, "speaker {}", speaker);
+            assert_eq!(labels.iter().filter(|l| *l == "truth").count(), 2, "speaker {}", speaker);
+        }
+    }
+}
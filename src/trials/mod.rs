@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use uuid::Builder;
+use yaml_rust::Yaml;
+
+use crate::stimuli::{Stimulus, StimulusManifest};
+use crate::trials::blocked::BlockedOrder;
+use crate::trials::fixed::FixedOrder;
+use crate::trials::latin_square::LatinSquareOrder;
+use crate::trials::random::RandomOrder;
+use crate::trials::run_limit::RunLengthLimit;
+use crate::views::TrialMetadata;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trial {
+    pub stimulus: Stimulus,
+    /// Presentation position within the participant's session, starting at 0
+    pub position: usize,
+    /// Links every record of this trial (dynamic, dichotomous and confidence)
+    pub uuid: String,
+}
+
+impl Trial {
+    pub fn new(stimulus: &Stimulus) -> Trial {
+        Trial {
+            stimulus: stimulus.clone(),
+            position: 0,
+            uuid: String::default(),
+        }
+    }
+
+    pub fn path(&self) -> String {
+        self.stimulus.path.clone()
+    }
+
+    pub fn metadata(&self, condition: &str) -> TrialMetadata {
+        TrialMetadata {
+            uuid: self.uuid.clone(),
+            stimulus_id: self.stimulus.id.clone(),
+            ground_truth: self.stimulus.label.clone(),
+            speaker: self.stimulus.speaker.clone(),
+            duration_ms: self.stimulus.duration_ms,
+            tags: self.stimulus.tags_text(),
+            position: self.position,
+            condition: condition.to_string(),
+        }
+    }
+}
+
+/// Numbers the trials in presentation order and gives each one a UUID. The UUIDs come from the
+/// participant's seeded generator so they are regenerated along with the trial order.
+pub fn assign_ids(trials: &mut [Trial], rng: &mut ChaCha8Rng) {
+    for (i, trial) in trials.iter_mut().enumerate() {
+        trial.position = i;
+        trial.uuid = Builder::from_random_bytes(rng.gen()).into_uuid().to_string();
+    }
+}
+
+/// Decides which stimuli from the manifest a participant sees and in which order
+pub trait TrialOrderStrategy {
+    fn generate(&self, manifest: &StimulusManifest, participant: usize, rng: &mut ChaCha8Rng) -> Vec<Trial>;
+
+    /// Whether a participant always gets the same order, so generating it again can't change it
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+}
+
+/// Splits `num` trials as evenly as possible over the ground truth labels, giving leftovers to the first labels
+fn label_distribution(num: usize, label_count: usize) -> Vec<usize> {
+    (0..label_count)
+        .map(|i| num / label_count + if i < num % label_count { 1 } else { 0 })
+        .collect()
+}
+
+/// Reads the `videos` section of the config. The `design` entry selects the strategy
+/// (random, latin_square, fixed or blocked) and `max_run` limits runs of the same label.
+/// The file of a fixed design is relative to `root`.
+pub fn from_config(yaml_config: &Yaml, root: &Path) -> Box<dyn TrialOrderStrategy> {
+    let videos = &yaml_config["videos"];
+    let design = &videos["design"];
+
+    let num_vids: usize = if !videos["num"].is_badvalue() {
+        videos["num"].as_i64().expect("Could not read valid number of videos from config") as usize
+    } else {
+        4
+    };
+
+    let design_type = if !design["type"].is_badvalue() {
+        design["type"].as_str().expect("Could not read the trial design type from config")
+    } else {
+        "random"
+    };
+
+    let strategy: Box<dyn TrialOrderStrategy> = match design_type {
+        "random" => Box::new(RandomOrder::new(num_vids)),
+        "latin_square" => Box::new(LatinSquareOrder::new(num_vids)),
+        "fixed" => {
+            let file = design["file"].as_str().expect("The fixed trial design needs a file parameter");
+            Box::new(FixedOrder::load(&root.join(file)))
+        },
+        "blocked" => {
+            let block_size = design["block_size"].as_i64().expect("The blocked trial design needs a block_size parameter") as usize;
+            Box::new(BlockedOrder::new(num_vids, block_size))
+        },
+        _ => panic!("Unknown trial design '{}' in config", design_type),
+    };
+
+    if !design["max_run"].is_badvalue() {
+        let max_run = design["max_run"].as_i64().expect("Could not read max_run from config") as usize;
+        Box::new(RunLengthLimit::new(strategy, max_run))
+    } else {
+        strategy
+    }
+}
+
+pub mod random;
+pub mod latin_square;
+pub mod fixed;
+pub mod blocked;
+pub mod run_limit;
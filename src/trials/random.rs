@@ -0,0 +1,53 @@
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand_chacha::ChaCha8Rng;
+
+use crate::stimuli::StimulusManifest;
+use crate::trials::{label_distribution, Trial, TrialOrderStrategy};
+
+/// Samples `num` speakers without replacement and randomly picks one of their stimuli, keeping the
+/// ground truth labels as evenly split as possible
+pub struct RandomOrder {
+    num_vids: usize,
+}
+
+impl RandomOrder {
+    pub fn new(num_vids: usize) -> RandomOrder {
+        RandomOrder {
+            num_vids
+        }
+    }
+}
+
+impl TrialOrderStrategy for RandomOrder {
+    fn generate(&self, manifest: &StimulusManifest, _participant: usize, rng: &mut ChaCha8Rng) -> Vec<Trial> {
+        let labels = manifest.labels();
+        let mut speakers = manifest.speakers();
+        let mut label_dist = label_distribution(self.num_vids, labels.len());
+
+        let mut trials: Vec<Trial> = Vec::new();
+
+        for _ in 0..self.num_vids {
+            // Select a random speaker
+            let index = (0..speakers.len()).choose(rng).expect("There are not enough speakers in the manifest for the number of videos");
+
+            // Remove the speaker from the set so they cannot be picked again
+            let speaker = speakers.swap_remove(index);
+
+            // Pick one of the speaker's stimuli, preferring labels that still have trials left
+            let available: Vec<usize> = (0..labels.len())
+                .filter(|l| manifest.find(&speaker, &labels[*l]).is_some())
+                .collect();
+            let open: Vec<usize> = available.iter().copied().filter(|l| label_dist[*l] > 0).collect();
+
+            let label = *open.choose(rng)
+                .or_else(|| available.choose(rng))
+                .expect("A speaker in the manifest has no stimuli");
+
+            label_dist[label] = label_dist[label].saturating_sub(1);
+
+            trials.push(Trial::new(manifest.find(&speaker, &labels[label]).unwrap()));
+        }
+
+        trials
+    }
+}
@@ -0,0 +1,164 @@
+use rand_chacha::ChaCha8Rng;
+
+use crate::stimuli::StimulusManifest;
+use crate::trials::{Trial, TrialOrderStrategy};
+
+const MAX_ATTEMPTS: usize = 10000;
+
+/// Regenerates the order of another strategy until no label appears more than `max_run` times in a row.
+/// Deterministic strategies are only checked, since regenerating them gives the same order.
+pub struct RunLengthLimit {
+    strategy: Box<dyn TrialOrderStrategy>,
+    max_run: usize,
+}
+
+impl RunLengthLimit {
+    pub fn new(strategy: Box<dyn TrialOrderStrategy>, max_run: usize) -> RunLengthLimit {
+        if max_run == 0 {
+            panic!("max_run must be above 0");
+        }
+
+        RunLengthLimit {
+            strategy,
+            max_run
+        }
+    }
+
+    fn longest_run(trials: &[Trial]) -> usize {
+        let mut longest = 0;
+        let mut current = 0;
+
+        for (i, trial) in trials.iter().enumerate() {
+            if i > 0 && trials[i - 1].stimulus.label == trial.stimulus.label {
+                current += 1;
+            } else {
+                current = 1;
+            }
+
+            longest = longest.max(current);
+        }
+
+        longest
+    }
+}
+
+impl TrialOrderStrategy for RunLengthLimit {
+    fn generate(&self, manifest: &StimulusManifest, participant: usize, rng: &mut ChaCha8Rng) -> Vec<Trial> {
+        if self.strategy.is_deterministic() {
+            let trials = self.strategy.generate(manifest, participant, rng);
+
+            if RunLengthLimit::longest_run(&trials) > self.max_run {
+                panic!("The trial order of participant {} has a run longer than max_run ({}), change the design or max_run", participant, self.max_run);
+            }
+
+            return trials;
+        }
+
+        for _ in 0..MAX_ATTEMPTS {
+            let trials = self.strategy.generate(manifest, participant, rng);
+
+            if RunLengthLimit::longest_run(&trials) <= self.max_run {
+                return trials;
+            }
+        }
+
+        panic!("Could not find a trial order without runs longer than {} for participant {}", self.max_run, participant);
+    }
+
+    fn is_deterministic(&self) -> bool {
+        self.strategy.is_deterministic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::trials::fixed::FixedOrder;
+    use crate::trials::random::RandomOrder;
+
+    fn manifest(speakers: usize) -> StimulusManifest {
+        StimulusManifest::from_legacy_ids(&(1..=speakers).map(|i| i.to_string()).collect::<Vec<String>>())
+    }
+
+    fn ids(trials: &[Trial]) -> Vec<String> {
+        trials.iter().map(|t| t.stimulus.id.clone()).collect()
+    }
+
+    #[test]
+    fn no_seed_gives_a_run_longer_than_the_limit() {
+        let manifest = manifest(8);
+
+        for max_run in 1..=3 {
+            let order = RunLengthLimit::new(Box::new(RandomOrder::new(8)), max_run);
+
+            for seed in 0..200 {
+                let trials = order.generate(&manifest, 1, &mut ChaCha8Rng::seed_from_u64(seed));
+                assert!(RunLengthLimit::longest_run(&trials) <= max_run, "max_run {}, seed {}", max_run, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn retries_give_the_same_order_for_the_same_seed() {
+        let manifest = manifest(8);
+        let order = RunLengthLimit::new(Box::new(RandomOrder::new(8)), 1);
+
+        for seed in 0..50 {
+            let first = order.generate(&manifest, 1, &mut ChaCha8Rng::seed_from_u64(seed));
+            let second = order.generate(&manifest, 1, &mut ChaCha8Rng::seed_from_u64(seed));
+
+            assert_eq!(ids(&first), ids(&second), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn longest_run_counts_the_same_label_in_a_row() {
+        let manifest = manifest(6);
+        let trials: Vec<Trial> = ["1_lie", "2_lie", "3_truth", "4_lie", "5_lie", "6_lie"].iter()
+            .map(|id| Trial::new(manifest.get(id).unwrap()))
+            .collect();
+
+        assert_eq!(RunLengthLimit::longest_run(&trials), 3);
+        assert_eq!(RunLengthLimit::longest_run(&trials[..3]), 2);
+        assert_eq!(RunLengthLimit::longest_run(&[]), 0);
+    }
+
+    #[test]
+    fn deterministic_orders_within_the_limit_are_kept() {
+        let fixed = FixedOrder::parse("participant,speaker,label\n*,1,lie\n*,2,lie\n*,3,truth\n", "order.csv");
+        let order = RunLengthLimit::new(Box::new(fixed), 2);
+
+        let trials = order.generate(&manifest(3), 1, &mut ChaCha8Rng::seed_from_u64(0));
+
+        assert!(order.is_deterministic());
+        assert_eq!(ids(&trials), vec!["1_lie", "2_lie", "3_truth"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The trial order of participant 1 has a run longer than max_run (1)")]
+    fn deterministic_orders_over_the_limit_are_rejected_without_retrying() {
+        let fixed = FixedOrder::parse("participant,speaker,label\n*,1,lie\n*,2,lie\n*,3,truth\n", "order.csv");
+        let order = RunLengthLimit::new(Box::new(fixed), 1);
+
+        order.generate(&manifest(3), 1, &mut ChaCha8Rng::seed_from_u64(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Could not find a trial order without runs longer than 1 for participant 1")]
+    fn orders_that_never_meet_the_limit_stop_after_the_attempts() {
+        let only_lies = StimulusManifest {
+            stimuli: manifest(4).stimuli.into_iter().filter(|s| s.label == "lie").collect(),
+        };
+        let order = RunLengthLimit::new(Box::new(RandomOrder::new(4)), 1);
+
+        order.generate(&only_lies, 1, &mut ChaCha8Rng::seed_from_u64(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "max_run must be above 0")]
+    fn a_limit_of_zero_is_rejected() {
+        RunLengthLimit::new(Box::new(RandomOrder::new(4)), 0);
+    }
+}
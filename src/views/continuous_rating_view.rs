@@ -0,0 +1,750 @@
+use std::env::current_exe;
+use std::time::{Duration, Instant};
+
+use iced::Alignment;
+use iced::widget::{Column, Image, Text};
+use iced::Element;
+
+use iced::Length;
+use iced_native::image;
+use iced_video_player::VideoPlayer;
+use url::Url;
+use yaml_rust::Yaml;
+
+use surface_dial_rs::events::{DialEvent, DialDirection, TopLevelEvent};
+
+use crate::Message;
+use crate::clock;
+use crate::connection::{ConnectionChange, ConnectionLog};
+use crate::arc_input::{ArcInput, ArcStyle, DisplayMode, PointerEvent};
+use crate::data::raw::RawEventLog;
+use crate::protocol::read_i32_or;
+
+use crate::views::ScreenCommand;
+use crate::views::DialView;
+
+use super::ExperimentData;
+use super::Printable;
+use super::TrialMetadata;
+use super::ArcSettings;
+use super::InputModalities;
+use super::DataRecord;
+use super::to_csv_rows;
+use super::recording::{Recorder, RecordingPolicy};
+use super::mapping::{ValueMapper, ValueMapping};
+use super::lock_in::{LockInPolicy, LockInRules, SideChanges};
+use crate::data::schema::{EVENT_COUNTERBALANCE, EVENT_DECISION, EVENT_DISPLAY, EVENT_FINAL, EVENT_LOCK_IN, EVENT_PATH, EVENT_SIDE_CHANGE, EVENT_SIDE_CHANGES, VALUE_UNDECIDED};
+
+/// What the participant rates
+pub enum Stimulus {
+    None,
+    Image(String),
+    Video(String),
+    /// Played through the video player without showing any frames
+    Audio(String),
+}
+
+impl Stimulus {
+    fn path(&self) -> Option<&String> {
+        match self {
+            Stimulus::None => None,
+            Stimulus::Image(path) | Stimulus::Video(path) | Stimulus::Audio(path) => Some(path),
+        }
+    }
+
+    /// Whether the stimulus plays and ends on its own
+    pub fn is_media(&self) -> bool {
+        matches!(self, Stimulus::Video(_) | Stimulus::Audio(_))
+    }
+}
+
+/// The values the arc can take
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResponseScale {
+    /// Any value between `min` and `max`
+    Continuous { min: i32, max: i32, initial: i32, anchor: i32 },
+    /// Snaps to one of two sides, starting undecided in the middle
+    Binary,
+    /// The values from `min` to `max` in steps of `step`, one step per detent
+    Discrete { min: i32, max: i32, step: i32, initial: i32, anchor: i32 },
+}
+
+impl ResponseScale {
+    /// Reads a screen's `scale` entry, e.g. `{ type: continuous, min: 0, max: 100, initial: 0, anchor: 0 }`,
+    /// `{ type: binary }` or `{ type: discrete, min: 1, max: 7, step: 1, initial: 4 }`
+    pub fn from_yaml(yaml: &Yaml) -> ResponseScale {
+        let scale_type = yaml["type"].as_str().expect("The response scale is missing its type");
+
+        let scale = match scale_type {
+            "binary" => return ResponseScale::Binary,
+            "continuous" => {
+                let min = read_i32_or(yaml, "min", 0);
+
+                ResponseScale::Continuous {
+                    min,
+                    max: read_i32_or(yaml, "max", 100),
+                    initial: read_i32_or(yaml, "initial", min),
+                    anchor: read_i32_or(yaml, "anchor", min),
+                }
+            },
+            "discrete" => {
+                let min = read_i32_or(yaml, "min", 1);
+
+                ResponseScale::Discrete {
+                    min,
+                    max: read_i32_or(yaml, "max", 7),
+                    step: read_i32_or(yaml, "step", 1),
+                    initial: read_i32_or(yaml, "initial", min),
+                    anchor: read_i32_or(yaml, "anchor", min),
+                }
+            },
+            _ => panic!("Unknown response scale '{}' in the protocol", scale_type),
+        };
+
+        let (min, max) = scale.bounds();
+
+        if max <= min || scale.initial() < min || scale.initial() > max || scale.anchor() < min || scale.anchor() > max {
+            panic!("The response scale needs min < max and an initial value and anchor between them");
+        }
+
+        if let ResponseScale::Discrete { min, max, step, initial, .. } = scale {
+            if step <= 0 || (max - min) % step != 0 || (initial - min) % step != 0 {
+                panic!("The discrete scale's step must be positive and divide the scale, with the initial value on a step");
+            }
+        }
+
+        scale
+    }
+
+    pub fn bounds(&self) -> (i32, i32) {
+        match *self {
+            ResponseScale::Continuous { min, max, .. } | ResponseScale::Discrete { min, max, .. } => (min, max),
+            ResponseScale::Binary => (-1, 1),
+        }
+    }
+
+    pub fn initial(&self) -> i32 {
+        match *self {
+            ResponseScale::Continuous { initial, .. } | ResponseScale::Discrete { initial, .. } => initial,
+            ResponseScale::Binary => 0,
+        }
+    }
+
+    pub fn anchor(&self) -> i32 {
+        match *self {
+            ResponseScale::Continuous { anchor, .. } | ResponseScale::Discrete { anchor, .. } => anchor,
+            ResponseScale::Binary => 0,
+        }
+    }
+
+    /// Puts a value from a pointer onto the scale
+    fn snap(&self, value: i32) -> i32 {
+        match *self {
+            ResponseScale::Continuous { .. } => value,
+            ResponseScale::Binary => value.signum(),
+            ResponseScale::Discrete { min, step, .. } => min + ((value - min) as f32 / step as f32).round() as i32 * step,
+        }
+    }
+
+    /// Whether the participant may pick `value`, binary scales can't go back to undecided
+    fn accepts(&self, value: i32) -> bool {
+        *self != ResponseScale::Binary || value != 0
+    }
+}
+
+/// Everything that sets a rating screen apart, the lie/truth and scale screens are presets of it
+pub struct RatingSpec {
+    /// Prefix of the output file name
+    pub name: String,
+    pub title: String,
+    /// Shown between the arc and the continue text
+    pub footer: Option<String>,
+    pub stimulus: Stimulus,
+    pub scale: ResponseScale,
+    pub lock_in: LockInPolicy,
+    pub lock_in_rules: LockInRules,
+    pub left_label: String,
+    pub right_label: String,
+    pub anchors: Vec<(i32, String)>,
+    /// The participant's counterbalancing for lie/truth screens, which mirrors the scale. The output is always unmirrored.
+    pub counterbalance: Option<bool>,
+    /// Stores the velocity of the changes in an extra column
+    pub record_velocity: bool,
+    pub recording: RecordingPolicy,
+    pub haptics: ArcSettings,
+    pub mapping: ValueMapping,
+    pub display: DisplayMode,
+    pub style: ArcStyle,
+    /// Start of the participant's session, the raw event timestamps count from it
+    pub session_start: Instant,
+}
+
+struct DataStructure {
+    id: usize,
+    name: String,
+    path: Option<String>,
+    counterbalance: Option<bool>,
+    scale: ResponseScale,
+    record_velocity: bool,
+    trial: Option<TrialMetadata>,
+    haptics: ArcSettings,
+    mapping: ValueMapping,
+    display: DisplayMode,
+    lock_in: LockInPolicy,
+    lock_in_rules: LockInRules,
+    /// (timestamp, value) of the press that locked in, before any revision
+    locked_in: Option<(u128, i32)>,
+    side_changes: SideChanges,
+    final_decision: i32,
+    final_decision_timestamp: u128,
+    data_points: Vec<DataPoint>,
+    connection: ConnectionLog,
+    inputs: InputModalities,
+}
+
+struct DataPoint {
+    timestamp: u128,
+    value: i32,
+    velocity: f32
+}
+
+impl DataStructure {
+    pub fn new(id: usize, spec: &RatingSpec, trial: Option<TrialMetadata>) -> DataStructure {
+        DataStructure {
+            id,
+            name: spec.name.clone(),
+            path: spec.stimulus.path().cloned(),
+            counterbalance: spec.counterbalance,
+            scale: spec.scale,
+            record_velocity: spec.record_velocity,
+            trial,
+            haptics: spec.haptics,
+            mapping: spec.mapping,
+            display: spec.display,
+            lock_in: spec.lock_in,
+            lock_in_rules: spec.lock_in_rules,
+            locked_in: None,
+            side_changes: SideChanges::new(spec.scale.anchor()),
+            final_decision: 0,
+            final_decision_timestamp: 0,
+            data_points: Vec::new(),
+            connection: ConnectionLog::new(),
+            inputs: InputModalities::new(),
+        }
+    }
+
+    /// Undoes the counterbalancing. Binary decisions are written as 0 (lie) and 1 (truth), or as `undecided`
+    /// when the end of the stimulus made the decision final before either side was picked.
+    fn output(&self, value: i32) -> String {
+        let (min, max) = self.scale.bounds();
+        let value = if self.counterbalance == Some(true) { min + max - value } else { value };
+
+        match self.scale {
+            ResponseScale::Binary if value == 0 => VALUE_UNDECIDED.to_string(),
+            ResponseScale::Binary => value.max(0).to_string(),
+            _ => value.to_string(),
+        }
+    }
+
+    fn padding(&self) -> &'static str {
+        if self.record_velocity { ",0.0" } else { "" }
+    }
+}
+
+impl ExperimentData for DataStructure {
+    fn name(&self) -> String {
+        format!("{}_{}", self.name, self.id).to_string()
+    }
+
+    fn headers(&self) -> String {
+        if self.record_velocity {
+            "type,timestamp,value,velocity".to_string()
+        } else {
+            "type,timestamp,value".to_string()
+        }
+    }
+
+    fn data(&self) -> Box<&dyn Printable> {
+        Box::new(self)
+    }
+
+    fn records(&self) -> Vec<DataRecord> {
+        let trial = self.trial.as_ref().map(|t| t.position);
+        let mut records: Vec<DataRecord> = Vec::new();
+
+        if let Some(counterbalance) = self.counterbalance {
+            records.push(DataRecord::metadata(trial, EVENT_COUNTERBALANCE, counterbalance.to_string()));
+        }
+        if let Some(path) = &self.path {
+            records.push(DataRecord::metadata(trial, EVENT_PATH, path.clone()));
+        }
+        if let Some(trial) = &self.trial {
+            records.append(&mut trial.to_records());
+        }
+        records.append(&mut self.haptics.to_records(trial));
+        if self.scale != ResponseScale::Binary {
+            records.append(&mut self.mapping.to_records(trial));
+        }
+        records.push(DataRecord::metadata(trial, EVENT_DISPLAY, self.display.name().to_string()));
+        records.append(&mut self.lock_in_rules.to_records(self.lock_in, trial));
+
+        for point in self.data_points.iter() {
+            let velocity = if self.record_velocity { Some(point.velocity) } else { None };
+            records.push(DataRecord::sample(trial, EVENT_DECISION, point.timestamp, self.output(point.value), velocity));
+        }
+        for (timestamp, value) in self.side_changes.changes() {
+            records.push(DataRecord::sample(trial, EVENT_SIDE_CHANGE, *timestamp, self.output(*value), None));
+        }
+        records.push(DataRecord::metadata(trial, EVENT_SIDE_CHANGES, self.side_changes.count().to_string()));
+        if let Some((timestamp, value)) = self.locked_in {
+            records.push(DataRecord::sample(trial, EVENT_LOCK_IN, timestamp, self.output(value), None));
+        }
+        records.append(&mut self.inputs.to_records(trial));
+        records.append(&mut self.connection.to_records(trial));
+        records.push(DataRecord::sample(trial, EVENT_FINAL, self.final_decision_timestamp, self.output(self.final_decision), None));
+
+        records
+    }
+}
+
+impl Printable for DataStructure {
+    fn to_csv(&self) -> String {
+        to_csv_rows(&self.records(), self.padding())
+    }
+}
+
+/// A stimulus rated on the arc, the trial screen every arc paradigm is configured from
+pub struct ContinuousRatingView {
+    title: String,
+    footer: Option<String>,
+    stimulus: Stimulus,
+    scale: ResponseScale,
+    lock_in: LockInPolicy,
+    rules: LockInRules,
+    arc_input: ArcInput,
+    mapper: ValueMapper,
+    value: i32,
+    velocity: f32,
+    samples: u32,
+    interim_decision: i32,
+    player: Option<VideoPlayer>,
+    data: DataStructure,
+    raw: RawEventLog,
+    // When the value last changed, used for the velocity between changes
+    last_change: Option<Instant>,
+    recorder: Recorder,
+    // The first press of a lock-in that needs confirming
+    armed: bool,
+    // The end of the revision window after locking in
+    revision_until: Option<Instant>,
+    finished: bool,
+    show_time: Instant
+}
+
+impl ContinuousRatingView {
+    pub fn new(id: usize, spec: RatingSpec, trial: Option<TrialMetadata>) -> ContinuousRatingView {
+        let (min, max) = spec.scale.bounds();
+        let mirrored = spec.counterbalance == Some(true);
+
+        let mut arc_input = ArcInput::new(min, max, spec.scale.anchor(), spec.scale.initial(), 90.0);
+        if mirrored {
+            arc_input.set_right_label(spec.left_label.clone());
+            arc_input.set_left_label(spec.right_label.clone());
+        } else {
+            arc_input.set_left_label(spec.left_label.clone());
+            arc_input.set_right_label(spec.right_label.clone());
+        }
+
+        // Anchors are given for the unmirrored scale, so they move with the labels
+        for (value, label) in spec.anchors.iter() {
+            arc_input.set_label(if mirrored { min + max - value } else { *value }, label.clone());
+        }
+
+        // Leave room for the stimulus above the arc
+        arc_input.scale(if matches!(spec.stimulus, Stimulus::None | Stimulus::Audio(_)) { 2.0 } else { 1.4 });
+        arc_input.set_style(spec.style);
+        arc_input.set_display(spec.display);
+
+        // Discrete scales always move one step per detent
+        let mapping = match spec.scale {
+            ResponseScale::Discrete { step, .. } => ValueMapping::Linear { step },
+            _ => spec.mapping,
+        };
+        let mut mapper = ValueMapper::new(mapping, min, max, spec.haptics.active_divisions());
+        mapper.reset(spec.scale.initial());
+
+        let data = DataStructure::new(id, &spec, trial);
+        let raw = RawEventLog::new(data.name(), data.trial.as_ref().map(|t| t.position), spec.session_start);
+
+        ContinuousRatingView {
+            title: spec.title,
+            footer: spec.footer,
+            stimulus: spec.stimulus,
+            scale: spec.scale,
+            lock_in: spec.lock_in,
+            rules: spec.lock_in_rules,
+            arc_input,
+            mapper,
+            value: spec.scale.initial(),
+            velocity: 0.0,
+            samples: 0,
+            interim_decision: spec.scale.initial(),
+            player: None,
+            data,
+            raw,
+            last_change: None,
+            recorder: Recorder::new(spec.recording),
+            armed: false,
+            revision_until: None,
+            finished: false,
+            show_time: clock::now()
+        }
+    }
+
+    /// The media position at an earlier instant, or the time since the screen was shown for stimuli that don't play
+    fn position_at(&self, at: Instant) -> Duration {
+        let now = if self.stimulus.is_media() {
+            self.player.as_ref().map_or(Duration::ZERO, |p| p.position())
+        } else {
+            clock::now().saturating_duration_since(self.show_time)
+        };
+
+        now.saturating_sub(clock::now().saturating_duration_since(at))
+    }
+
+    /// Stores the value the dial had at `at`
+    fn record_point(&mut self, at: Instant) {
+        self.data.data_points.push(DataPoint {
+            timestamp: self.position_at(at).as_millis(),
+            value: self.value,
+            velocity: if self.samples > 0 { self.velocity / self.samples as f32 } else { 0.0 }
+        });
+        self.last_change = None;
+        self.velocity = 0.0;
+        self.samples = 0;
+    }
+
+    /// Clears the decision, called whenever the screen is entered
+    fn reset(&mut self) {
+        let initial = self.scale.initial();
+
+        self.value = initial;
+        self.interim_decision = initial;
+        self.mapper.reset(initial);
+        self.arc_input.set_value(initial);
+        self.arc_input.set_disabled(false);
+        self.finished = false;
+        self.data.data_points.clear();
+        self.data.final_decision = 0;
+        self.data.final_decision_timestamp = 0;
+        self.data.locked_in = None;
+        self.data.side_changes.reset(initial);
+        self.armed = false;
+        self.revision_until = None;
+        self.raw.clear();
+        self.data.connection.clear();
+        self.data.inputs.clear();
+    }
+
+    /// The value after one detent of the dial
+    fn rotate(&mut self, direction: &DialDirection, velocity: f32) -> i32 {
+        match self.scale {
+            ResponseScale::Binary => match direction {
+                DialDirection::Clockwise => 1,
+                DialDirection::Counterclockwise => -1,
+            },
+            _ => self.mapper.rotate(direction, velocity),
+        }
+    }
+
+    /// Applies a new value from the dial or a pointer
+    fn change_value(&mut self, value: i32, at: Instant) {
+        if self.arc_input.is_disabled() || !self.scale.accepts(value) {
+            return;
+        }
+
+        // Once the changes are used up the value can't cross over to the other side
+        if let Some(max_changes) = self.rules.max_changes {
+            if self.data.side_changes.count() >= max_changes as usize && self.data.side_changes.is_change(value) {
+                self.mapper.reset(self.value);
+                return;
+            }
+        }
+
+        self.value = value;
+
+        let old_timer = self.last_change;
+
+        if self.interim_decision != self.value {
+            let timestamp = self.position_at(at).as_millis();
+
+            self.interim_decision = self.value;
+            self.last_change = Some(at);
+            self.recorder.changed(at);
+            self.data.side_changes.update(self.value, timestamp);
+            self.armed = false;
+
+            // Calculate ticks per second
+            if let Some(ot) = old_timer {
+                let time_diff = at.saturating_duration_since(ot).as_millis();
+                self.velocity += 1.0 / (time_diff as f32 / 1000.0);
+            } else {
+                self.velocity = 1.0;
+            }
+            self.samples += 1;
+        }
+
+        self.arc_input.set_value(self.value);
+    }
+
+    /// Makes the current value the final decision
+    fn finish(&mut self, at: Instant) {
+        self.data.final_decision = self.value;
+        self.data.final_decision_timestamp = self.position_at(at).as_millis();
+        self.recorder.stop();
+
+        self.arc_input.set_disabled(true);
+        if let Some(p) = self.player.as_mut() {
+            p.set_paused(true);
+        }
+
+        self.revision_until = None;
+        self.finished = true;
+    }
+
+    /// Locks in the decision if the policy and its rules allow it, or continues once it is final
+    fn press(&mut self, at: Instant) -> ScreenCommand {
+        if self.finished {
+            return ScreenCommand::NextScreen(None);
+        }
+
+        // Pressing again during the revision window ends it early
+        if self.revision_until.is_some() {
+            self.finish(at);
+            return ScreenCommand::None;
+        }
+
+        if !self.lock_in.on_press() || !self.scale.accepts(self.value) || self.position_at(at) < self.rules.min_viewing {
+            return ScreenCommand::None;
+        }
+
+        if self.rules.confirm && !self.armed {
+            self.armed = true;
+            return ScreenCommand::None;
+        }
+
+        self.armed = false;
+        self.data.locked_in = Some((self.position_at(at).as_millis(), self.value));
+
+        if self.rules.revision_window > Duration::ZERO {
+            self.revision_until = Some(at + self.rules.revision_window);
+        } else {
+            self.finish(at);
+        }
+
+        ScreenCommand::None
+    }
+}
+
+impl DialView for ContinuousRatingView {
+    fn init(&mut self) {
+
+    }
+
+    fn update(&mut self, msg: Option<TopLevelEvent>, at: Instant) -> ScreenCommand {
+        let mut command = ScreenCommand::None;
+
+        for point in self.recorder.due(at) {
+            self.record_point(point);
+        }
+
+        match msg {
+            Some(e) => {
+                self.raw.record(&e, self.position_at(at), at);
+
+                if let TopLevelEvent::DialEvent(d) = &e {
+                    self.data.inputs.add("dial");
+
+                    match d {
+                        DialEvent::Rotate { direction, velocity } => {
+                            if !self.arc_input.is_disabled() {
+                                let value = self.rotate(direction, *velocity as f32);
+                                self.change_value(value, at);
+                            }
+                        },
+                        DialEvent::Button { pressed: true } => command = self.press(at),
+                        _ => {}
+                    }
+                }
+            },
+            _ => {}
+        }
+
+        if let Some(until) = self.revision_until.filter(|u| *u <= at) {
+            self.finish(until);
+        }
+
+        // Check to see if we've reached the end of the stimulus (with some buffer)
+        let ended = self.player.as_ref().map_or(false, |p| p.position().as_millis() + 25 >= p.duration().as_millis());
+
+        if ended && !self.finished && (self.revision_until.is_some() || self.lock_in.at_end()) {
+            self.finish(clock::now());
+        }
+
+        command
+    }
+
+    fn view(&self) -> Element<Message> {
+        let mut column = Column::new()
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(40)
+            .align_items(Alignment::Center)
+            .push(Text::new(self.title.clone()).size(30));
+
+        match &self.stimulus {
+            Stimulus::Image(path) => {
+                column = column.push(Image::new(image::Handle::from_path(path)).width(Length::Units(640)).height(Length::Units(360)));
+            },
+            Stimulus::Video(_) => {
+                if let Some(v) = self.player.as_ref() {
+                    column = column.push(v.frame_view().width(Length::Units(640)).height(Length::Units(360)));
+                } else {
+                    column = column.push(Text::new("Video is loading"));
+                }
+            },
+            Stimulus::None | Stimulus::Audio(_) => {}
+        }
+
+        column = column.push(self.arc_input.view());
+
+        let continue_text = Text::new(if self.finished {
+            "Press down on the dial to continue"
+        } else if self.armed {
+            "Press down again to lock in your decision"
+        } else if self.revision_until.is_some() {
+            "You can still change your decision, press down to make it final"
+        } else {
+            ""
+        });
+
+        match &self.footer {
+            Some(footer) => column
+                .push(Text::new("\n\n0").size(22).height(Length::Shrink))
+                .push(Text::new(footer.clone()).size(16))
+                .push(Text::new("\n\n\n0").size(22).height(Length::Shrink))
+                .push(continue_text.size(18))
+                .into(),
+            None => column.push(continue_text.size(25)).into(),
+        }
+    }
+
+    fn show(&mut self) {
+        self.show_time = clock::now();
+
+        if self.stimulus.is_media() {
+            let path = std::path::PathBuf::from(current_exe().unwrap());
+            let root_path = path.parent().unwrap().parent().unwrap().parent().unwrap();
+            let stimulus_path = self.stimulus.path().expect("A media stimulus has no path");
+            let uri = Url::from_file_path(root_path.join(stimulus_path).canonicalize().unwrap()).unwrap();
+
+            self.player = Some(VideoPlayer::new(&uri, false).unwrap());
+            self.player.as_mut().expect("No stimulus is loaded").set_paused(false);
+        }
+
+        self.reset();
+        self.recorder.start(clock::now());
+    }
+
+    fn hide(&mut self) {
+        if let Some(p) = self.player.as_mut() {
+            p.set_paused(true);
+        }
+        self.player = None;
+    }
+
+    fn data(&self) -> Option<Box<&dyn super::ExperimentData>> {
+        Some(Box::new(&self.data))
+    }
+
+    fn raw_data(&self) -> Option<Box<&dyn super::ExperimentData>> {
+        Some(Box::new(&self.raw))
+    }
+
+    fn connection_changed(&mut self, change: ConnectionChange) {
+        match change {
+            ConnectionChange::Lost { at } => {
+                self.data.connection.lost(self.position_at(at).as_millis());
+
+                if let Some(p) = self.player.as_mut() {
+                    p.set_paused(true);
+                }
+            },
+            ConnectionChange::Restored { at, outage, compromised } => {
+                // Media stood still during the outage, so it resumes from where it was paused
+                let position = match self.player.as_ref() {
+                    Some(p) => p.position(),
+                    None => self.position_at(at),
+                };
+                self.data.connection.restored(position.as_millis(), outage, compromised);
+
+                let finished = self.finished;
+                if let Some(p) = self.player.as_mut() {
+                    p.set_paused(finished);
+                }
+            },
+        }
+    }
+
+    fn arc_settings(&self) -> Option<super::ArcSettings> {
+        Some(self.data.haptics)
+    }
+
+    fn allow_back(&self) -> bool {
+        false
+    }
+
+    fn takes_pointer(&self) -> bool {
+        true
+    }
+
+    fn iced_input(&mut self, msg: Message) -> ScreenCommand {
+        let mut command = ScreenCommand::None;
+
+        if let Message::Pointer(pointer) = msg {
+            let at = clock::now();
+
+            for point in self.recorder.due(at) {
+                self.record_point(point);
+            }
+
+            self.raw.record_pointer(&pointer, self.position_at(at), at);
+            self.data.inputs.add(pointer.kind().name());
+
+            match pointer {
+                PointerEvent::Value { kind: _, value } => {
+                    if !self.arc_input.is_disabled() {
+                        let value = self.scale.snap(value);
+                        self.mapper.reset(value);
+                        self.change_value(value, at);
+                    }
+                },
+                PointerEvent::Rotated { steps } => {
+                    if !self.arc_input.is_disabled() {
+                        let direction = if steps > 0 { DialDirection::Clockwise } else { DialDirection::Counterclockwise };
+                        let mut value = self.value;
+
+                        for _ in 0..steps.abs() {
+                            value = self.rotate(&direction, 1.0);
+                        }
+
+                        self.change_value(value, at);
+                    }
+                },
+                PointerEvent::Pressed { kind: _ } => command = self.press(at),
+            }
+        }
+
+        command
+    }
+}
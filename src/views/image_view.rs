@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use iced::Alignment;
 use iced::widget::{Column, Image, Text};
 use iced::Element;
@@ -30,7 +32,7 @@ impl DialView for ImageView {
         
     }
 
-    fn update(&mut self, msg: Option<TopLevelEvent>) -> ScreenCommand {
+    fn update(&mut self, msg: Option<TopLevelEvent>, _at: Instant) -> ScreenCommand {
         match msg {
             Some(e) => {
                 if let TopLevelEvent::DialEvent(DialEvent::Button { pressed }) = &e {
@@ -75,9 +77,7 @@ impl DialView for ImageView {
     }
 
     fn arc_settings(&self) -> Option<super::ArcSettings> {
-        Some(super::ArcSettings {
-            divisions: 0
-        })
+        Some(super::ArcSettings::detents(0))
     }
 
     fn iced_input(&mut self, msg: Message) -> ScreenCommand {
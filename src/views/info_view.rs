@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use iced::Alignment;
 use iced::widget::Column;
 use iced::Element;
@@ -31,7 +33,7 @@ impl DialView for InfoView {
         
     }
 
-    fn update(&mut self, msg: Option<TopLevelEvent>) -> ScreenCommand {
+    fn update(&mut self, msg: Option<TopLevelEvent>, _at: Instant) -> ScreenCommand {
         match msg {
             Some(e) => {
                 if let TopLevelEvent::DialEvent(DialEvent::Button { pressed }) = &e {
@@ -74,9 +76,7 @@ impl DialView for InfoView {
     }
 
     fn arc_settings(&self) -> Option<super::ArcSettings> {
-        Some(super::ArcSettings {
-            divisions: 0
-        })
+        Some(super::ArcSettings::detents(0))
     }
 
     fn iced_input(&mut self, msg: Message) -> ScreenCommand {
@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use yaml_rust::Yaml;
+
+use crate::data::schema::{EVENT_LOCK_IN_CONFIRM, EVENT_LOCK_IN_MAX_CHANGES, EVENT_LOCK_IN_MIN_VIEWING, EVENT_LOCK_IN_POLICY, EVENT_LOCK_IN_REVISION};
+use crate::views::DataRecord;
+
+/// What makes the decision final
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LockInPolicy {
+    /// Pressing down on the dial
+    Button,
+    /// The end of the video or audio, whatever the value is at that point
+    EndOfStimulus,
+    /// Whichever of the two comes first
+    Either,
+}
+
+impl LockInPolicy {
+    /// Reads a screen's `lock_in` entry: `button`, `end_of_stimulus` or `either`. Screens without one lock in with the button.
+    pub fn from_yaml(yaml: &Yaml) -> LockInPolicy {
+        if yaml.is_badvalue() {
+            return LockInPolicy::Button;
+        }
+
+        match yaml.as_str().expect("Could not read the lock_in parameter") {
+            "button" => LockInPolicy::Button,
+            "end_of_stimulus" => LockInPolicy::EndOfStimulus,
+            "either" => LockInPolicy::Either,
+            p => panic!("Unknown lock-in policy '{}' in the protocol", p),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LockInPolicy::Button => "button",
+            LockInPolicy::EndOfStimulus => "end_of_stimulus",
+            LockInPolicy::Either => "either",
+        }
+    }
+
+    pub fn on_press(&self) -> bool {
+        *self != LockInPolicy::EndOfStimulus
+    }
+
+    pub fn at_end(&self) -> bool {
+        *self != LockInPolicy::Button
+    }
+}
+
+/// Extra conditions on locking in with the button. None of them apply without any rules.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LockInRules {
+    /// Presses before this much of the stimulus has played (or the screen has been shown) are ignored
+    pub min_viewing: Duration,
+    /// The first press only asks for confirmation, the second one locks in
+    pub confirm: bool,
+    /// How long the decision can still be revised after locking in. The value at the end of the window is final.
+    pub revision_window: Duration,
+    /// How often the participant may change sides, after which the value stays on its side
+    pub max_changes: Option<u32>,
+}
+
+fn read_ms_or_zero(yaml: &Yaml, key: &str) -> Duration {
+    if !yaml[key].is_badvalue() {
+        let ms = yaml[key].as_i64().expect(format!("Could not read the lock-in {} parameter", key).as_str());
+
+        if ms < 0 {
+            panic!("The lock-in {} parameter can't be negative", key);
+        }
+
+        Duration::from_millis(ms as u64)
+    } else {
+        Duration::ZERO
+    }
+}
+
+impl LockInRules {
+    /// Reads a screen's `lock_in_rules` entry, e.g. `{ min_viewing_ms: 5000, confirm: true, revision_window_ms: 2000, max_changes: 3 }`
+    pub fn from_yaml(yaml: &Yaml) -> LockInRules {
+        if yaml.is_badvalue() {
+            return LockInRules::default();
+        }
+
+        LockInRules {
+            min_viewing: read_ms_or_zero(yaml, "min_viewing_ms"),
+            confirm: if !yaml["confirm"].is_badvalue() {
+                yaml["confirm"].as_bool().expect("Could not read the lock-in confirm parameter")
+            } else {
+                false
+            },
+            revision_window: read_ms_or_zero(yaml, "revision_window_ms"),
+            max_changes: if !yaml["max_changes"].is_badvalue() {
+                let max_changes = yaml["max_changes"].as_i64().expect("Could not read the lock-in max_changes parameter");
+
+                if max_changes < 0 {
+                    panic!("The lock-in max_changes parameter can't be negative");
+                }
+
+                Some(max_changes as u32)
+            } else {
+                None
+            },
+        }
+    }
+
+    pub fn to_records(&self, policy: LockInPolicy, trial: Option<usize>) -> Vec<DataRecord> {
+        self.parameters(policy).into_iter()
+            .map(|(event, value)| DataRecord::metadata(trial, event, value))
+            .collect()
+    }
+
+    fn parameters(&self, policy: LockInPolicy) -> Vec<(&'static str, String)> {
+        vec![
+            (EVENT_LOCK_IN_POLICY, policy.name().to_string()),
+            (EVENT_LOCK_IN_MIN_VIEWING, self.min_viewing.as_millis().to_string()),
+            (EVENT_LOCK_IN_CONFIRM, self.confirm.to_string()),
+            (EVENT_LOCK_IN_REVISION, self.revision_window.as_millis().to_string()),
+            (EVENT_LOCK_IN_MAX_CHANGES, self.max_changes.map_or("none".to_string(), |m| m.to_string())),
+        ]
+    }
+}
+
+/// Tracks which side of the anchor the value is on and every change between the sides.
+/// Values on the anchor itself belong to neither side, so moving through it only counts once.
+pub struct SideChanges {
+    anchor: i32,
+    side: i32,
+    /// (timestamp, value) of every change
+    changes: Vec<(u128, i32)>,
+}
+
+impl SideChanges {
+    pub fn new(anchor: i32) -> SideChanges {
+        SideChanges {
+            anchor,
+            side: 0,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Forgets every change, starting from the side of `value`
+    pub fn reset(&mut self, value: i32) {
+        self.side = (value - self.anchor).signum();
+        self.changes.clear();
+    }
+
+    /// Whether moving to `value` would be a change of side
+    pub fn is_change(&self, value: i32) -> bool {
+        let side = (value - self.anchor).signum();
+        side != 0 && self.side != 0 && side != self.side
+    }
+
+    pub fn update(&mut self, value: i32, timestamp: u128) {
+        let side = (value - self.anchor).signum();
+
+        if self.is_change(value) {
+            self.changes.push((timestamp, value));
+        }
+        if side != 0 {
+            self.side = side;
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn changes(&self) -> &[(u128, i32)] {
+        &self.changes
+    }
+}
@@ -13,9 +13,11 @@ use url::Url;
 use surface_dial_rs::events::{DialEvent, TopLevelEvent};
 
 use crate::Message;
+use crate::connection::ConnectionChange;
 use crate::arc_input::ArcInput;
 
-use crate::views::{ExperimentData, Printable, ScreenCommand};
+use crate::views::{DataRecord, ExperimentData, Printable, ScreenCommand};
+use crate::data::schema::{EVENT_FINAL, EVENT_PATH};
 use crate::views::DialView;
 
 struct DataStructure {
@@ -46,6 +48,13 @@ impl ExperimentData for DataStructure {
     fn data(&self) -> Box<&dyn Printable> {
         Box::new(self)
     }
+
+    fn records(&self) -> Vec<DataRecord> {
+        vec![
+            DataRecord::metadata(Some(self.id), EVENT_PATH, self.path.clone()),
+            DataRecord::sample(Some(self.id), EVENT_FINAL, self.final_decision_timestamp, "0".to_string(), None),
+        ]
+    }
 }
 
 impl Printable for DataStructure {
@@ -81,7 +90,7 @@ impl DialView for LockInVideoView {
 
     }
 
-    fn update(&mut self, msg: Option<TopLevelEvent>) -> ScreenCommand {
+    fn update(&mut self, msg: Option<TopLevelEvent>, _at: Instant) -> ScreenCommand {
         match msg {
             Some(e) => {
 
@@ -97,10 +106,6 @@ impl DialView for LockInVideoView {
                         }
                     }
                 }
-
-                if let TopLevelEvent::ConnectionEvent(c) = e {
-                    println!("Connection event: {:?}", c);
-                }
             },
             _ => {}
         }
@@ -152,10 +157,24 @@ impl DialView for LockInVideoView {
         None
     }
 
+    fn connection_changed(&mut self, change: ConnectionChange) {
+        // Nothing should play while the participant can't respond
+        if let Some(v) = self.video.as_mut() {
+            match change {
+                ConnectionChange::Lost { .. } => v.set_paused(true),
+                ConnectionChange::Restored { .. } => v.set_paused(self.finished),
+            }
+        }
+    }
+
     fn arc_settings(&self) -> Option<super::ArcSettings> {
         None
     }
 
+    fn allow_back(&self) -> bool {
+        false
+    }
+
     fn iced_input(&mut self, msg: Message) -> ScreenCommand {
         ScreenCommand::None
     }
@@ -0,0 +1,132 @@
+use surface_dial_rs::events::DialDirection;
+use yaml_rust::Yaml;
+
+use crate::data::schema::{EVENT_MAPPING, EVENT_MAPPING_GAIN, EVENT_MAPPING_MAX_STEP, EVENT_MAPPING_STEP};
+use crate::views::DataRecord;
+use crate::protocol::read_i32_or;
+
+/// Rotate events per full turn when the dial's subdivisions are disabled
+const UNDIVIDED_STEPS_PER_TURN: u16 = 3600;
+
+/// How a rotation of the dial changes the value of an arc screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueMapping {
+    /// The same step for every detent
+    Linear { step: i32 },
+    /// Steps grow with the velocity the dial reports, up to `max_step`
+    Accelerated { step: i32, gain: f32, max_step: i32 },
+    /// A full turn of the dial covers the whole scale
+    Absolute,
+}
+
+impl Default for ValueMapping {
+    fn default() -> ValueMapping {
+        ValueMapping::Linear { step: 1 }
+    }
+}
+
+impl ValueMapping {
+    /// Reads a screen's `mapping` entry, e.g. `{ type: linear, step: 5 }`, `{ type: accelerated, step: 1, gain: 0.5, max_step: 10 }`
+    /// or `{ type: absolute }`. Screens without one move by one per detent.
+    pub fn from_yaml(yaml: &Yaml) -> ValueMapping {
+        if yaml.is_badvalue() {
+            return ValueMapping::default();
+        }
+
+        let mapping_type = yaml["type"].as_str().expect("The value mapping is missing its type");
+
+        let mapping = match mapping_type {
+            "linear" => ValueMapping::Linear {
+                step: read_i32_or(yaml, "step", 1),
+            },
+            "accelerated" => ValueMapping::Accelerated {
+                step: read_i32_or(yaml, "step", 1),
+                gain: if !yaml["gain"].is_badvalue() {
+                    yaml["gain"].as_f64().expect("Could not read the mapping gain parameter") as f32
+                } else {
+                    1.0
+                },
+                max_step: read_i32_or(yaml, "max_step", 10),
+            },
+            "absolute" => ValueMapping::Absolute,
+            _ => panic!("Unknown value mapping '{}' in the protocol", mapping_type),
+        };
+
+        match mapping {
+            ValueMapping::Linear { step } | ValueMapping::Accelerated { step, .. } if step <= 0 => panic!("The mapping step must be positive"),
+            ValueMapping::Accelerated { step, max_step, .. } if max_step < step => panic!("The mapping max_step can't be below its step"),
+            _ => mapping,
+        }
+    }
+
+    pub fn to_records(&self, trial: Option<usize>) -> Vec<DataRecord> {
+        self.parameters().into_iter()
+            .map(|(event, value)| DataRecord::metadata(trial, event, value))
+            .collect()
+    }
+
+    fn parameters(&self) -> Vec<(&'static str, String)> {
+        match self {
+            ValueMapping::Linear { step } => vec![
+                (EVENT_MAPPING, "linear".to_string()),
+                (EVENT_MAPPING_STEP, step.to_string()),
+            ],
+            ValueMapping::Accelerated { step, gain, max_step } => vec![
+                (EVENT_MAPPING, "accelerated".to_string()),
+                (EVENT_MAPPING_STEP, step.to_string()),
+                (EVENT_MAPPING_GAIN, gain.to_string()),
+                (EVENT_MAPPING_MAX_STEP, max_step.to_string()),
+            ],
+            ValueMapping::Absolute => vec![
+                (EVENT_MAPPING, "absolute".to_string()),
+            ],
+        }
+    }
+}
+
+/// Turns rotate events into values under a mapping. The position is kept as a fraction,
+/// so absolute mapping doesn't lose the turns that are smaller than one value.
+pub struct ValueMapper {
+    mapping: ValueMapping,
+    min: i32,
+    max: i32,
+    steps_per_turn: u16,
+    position: f32,
+}
+
+impl ValueMapper {
+    /// `detents` is the number of rotate events per full turn, 0 if the subdivisions are disabled
+    pub fn new(mapping: ValueMapping, min: i32, max: i32, detents: u16) -> ValueMapper {
+        ValueMapper {
+            mapping,
+            min,
+            max,
+            steps_per_turn: if detents > 0 { detents } else { UNDIVIDED_STEPS_PER_TURN },
+            position: 0.0,
+        }
+    }
+
+    /// Starts mapping from `value`, called whenever the screen sets its value
+    pub fn reset(&mut self, value: i32) {
+        self.position = value as f32;
+    }
+
+    /// The value after one rotate event
+    pub fn rotate(&mut self, direction: &DialDirection, velocity: f32) -> i32 {
+        let step = match self.mapping {
+            ValueMapping::Linear { step } => step as f32,
+            ValueMapping::Accelerated { step, gain, max_step } => {
+                (step as f32 * (1.0 + gain * velocity.abs())).round().min(max_step as f32)
+            },
+            ValueMapping::Absolute => (self.max - self.min) as f32 / self.steps_per_turn as f32,
+        };
+
+        let delta = match direction {
+            DialDirection::Clockwise => step,
+            DialDirection::Counterclockwise => -step,
+        };
+
+        self.position = (self.position + delta).clamp(self.min as f32, self.max as f32);
+        self.position.round() as i32
+    }
+}
@@ -1,10 +1,15 @@
 use std::collections::HashMap;
+use std::time::Instant;
 
 use iced::Element;
 
 use surface_dial_rs::events::TopLevelEvent;
+use yaml_rust::Yaml;
 
 use crate::Message;
+use crate::connection::ConnectionChange;
+use crate::data::schema::{EVENT_CONDITION, EVENT_GROUND_TRUTH, EVENT_HAPTIC_DETENTS, EVENT_HAPTIC_ENABLED, EVENT_INPUT, EVENT_POSITION, EVENT_STIMULUS, EVENT_TRIAL_UUID, EVENT_SPEAKER,
+    EVENT_DURATION, EVENT_TAGS};
 
 
 pub trait ConfigurationPayload {
@@ -17,32 +22,205 @@ pub trait Printable {
 pub enum ScreenCommand {
     None,
     NextScreen(Option<HashMap<String, String>>),
-    PreviousScreen
+    PreviousScreen,
 }
 
+/// Identifies the trial a screen belongs to, so the dynamic, dichotomous and confidence records
+/// of one trial can be joined without relying on file names
+#[derive(Clone)]
+pub struct TrialMetadata {
+    pub uuid: String,
+    pub stimulus_id: String,
+    pub ground_truth: String,
+    pub speaker: String,
+    /// Length of the stimulus and its tags as `key=value;key=value`, as given in the manifest
+    pub duration_ms: Option<u64>,
+    pub tags: String,
+    pub position: usize,
+    pub condition: String,
+}
+
+impl TrialMetadata {
+    pub fn to_records(&self) -> Vec<DataRecord> {
+        let trial = Some(self.position);
+
+        vec![
+            DataRecord::metadata(trial, EVENT_TRIAL_UUID, self.uuid.clone()),
+            DataRecord::metadata(trial, EVENT_STIMULUS, self.stimulus_id.clone()),
+            DataRecord::metadata(trial, EVENT_GROUND_TRUTH, self.ground_truth.clone()),
+            DataRecord::metadata(trial, EVENT_SPEAKER, self.speaker.clone()),
+            // Empty when the manifest doesn't give them
+            DataRecord::metadata(trial, EVENT_DURATION, self.duration_ms.map_or(String::default(), |d| d.to_string())),
+            DataRecord::metadata(trial, EVENT_TAGS, self.tags.clone()),
+            DataRecord::metadata(trial, EVENT_POSITION, self.position.to_string()),
+            DataRecord::metadata(trial, EVENT_CONDITION, self.condition.clone()),
+        ]
+    }
+}
+
+/// A single event of a screen's data, used for the tidy long-format output
+pub struct DataRecord {
+    pub trial: Option<usize>,
+    pub event: String,
+    pub timestamp: Option<u128>,
+    pub value: String,
+    pub velocity: Option<f32>,
+}
+
+impl DataRecord {
+    pub fn metadata(trial: Option<usize>, event: &str, value: String) -> DataRecord {
+        DataRecord {
+            trial,
+            event: event.to_string(),
+            timestamp: None,
+            value,
+            velocity: None
+        }
+    }
+
+    pub fn sample(trial: Option<usize>, event: &str, timestamp: u128, value: String, velocity: Option<f32>) -> DataRecord {
+        DataRecord {
+            trial,
+            event: event.to_string(),
+            timestamp: Some(timestamp),
+            value,
+            velocity
+        }
+    }
+}
+
+/// Renders records in the `type,timestamp,value` layout of the trial CSVs. Metadata is written at timestamp 0
+/// and `padding` stands in for the velocity of records without one.
+pub fn to_csv_rows(records: &[DataRecord], padding: &str) -> String {
+    records.iter()
+        .map(|r| format!("{},{},{}{}\n",
+            r.event,
+            r.timestamp.unwrap_or(0),
+            r.value,
+            r.velocity.map_or(padding.to_string(), |v| format!(",{}", v))))
+        .collect()
+}
+
+/// Haptic profile of a screen
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ArcSettings {
-    pub divisions: u16
+    /// Detents per full turn, 0 turns them off
+    pub divisions: u16,
+    pub haptics: bool,
+}
+
+impl ArcSettings {
+    pub fn detents(divisions: u16) -> ArcSettings {
+        ArcSettings {
+            divisions,
+            haptics: true,
+        }
+    }
+
+    /// Overrides the profile with a screen's `haptics` entry, e.g. `{ enabled: true, detents: 20 }`
+    pub fn with_yaml(self, yaml: &Yaml) -> ArcSettings {
+        if yaml.is_badvalue() {
+            return self;
+        }
+
+        ArcSettings {
+            divisions: if !yaml["detents"].is_badvalue() {
+                yaml["detents"].as_i64().expect("Could not read the haptics detents parameter") as u16
+            } else {
+                self.divisions
+            },
+            haptics: if !yaml["enabled"].is_badvalue() {
+                yaml["enabled"].as_bool().expect("Could not read the haptics enabled parameter")
+            } else {
+                self.haptics
+            },
+        }
+    }
+
+    /// The subdivisions the dial is set to. Its detents are its only haptic feedback,
+    /// so turning the haptics off disables them.
+    pub fn active_divisions(&self) -> u16 {
+        if self.haptics { self.divisions } else { 0 }
+    }
+
+    pub fn to_records(&self, trial: Option<usize>) -> Vec<DataRecord> {
+        vec![
+            DataRecord::metadata(trial, EVENT_HAPTIC_ENABLED, self.haptics.to_string()),
+            DataRecord::metadata(trial, EVENT_HAPTIC_DETENTS, self.divisions.to_string()),
+        ]
+    }
+}
+
+/// The devices a screen's value was set with (`dial`, `mouse` or `touch`), in the order they were first used
+pub struct InputModalities {
+    used: Vec<&'static str>,
+}
+
+impl InputModalities {
+    pub fn new() -> InputModalities {
+        InputModalities {
+            used: Vec::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.used.clear();
+    }
+
+    pub fn add(&mut self, modality: &'static str) {
+        if !self.used.contains(&modality) {
+            self.used.push(modality);
+        }
+    }
+
+    /// E.g. `dial+mouse`, or `none` if the screen got no input
+    fn value(&self) -> String {
+        if self.used.is_empty() {
+            "none".to_string()
+        } else {
+            self.used.join("+")
+        }
+    }
+
+    pub fn to_records(&self, trial: Option<usize>) -> Vec<DataRecord> {
+        vec![DataRecord::metadata(trial, EVENT_INPUT, self.value())]
+    }
 }
 
 pub trait ExperimentData {
     fn name(&self) -> String;
     fn headers(&self) -> String;
     fn data(&self) -> Box<&dyn Printable>;
+    fn records(&self) -> Vec<DataRecord>;
 }
 
 pub trait DialView {
     fn init(&mut self);
-    fn update(&mut self, msg: Option<TopLevelEvent>) -> ScreenCommand;
+    /// `at` is when the dial event arrived, or the current time for the regular tick without an event
+    fn update(&mut self, msg: Option<TopLevelEvent>, at: Instant) -> ScreenCommand;
     fn iced_input(&mut self, msg: Message) -> ScreenCommand;
     fn view(&self) -> Element<Message>;
     fn show(&mut self);
     fn hide(&mut self);
     fn data(&self) -> Option<Box<&dyn ExperimentData>>;
+    /// Every dial event the screen received, for screens that record decision dynamics
+    fn raw_data(&self) -> Option<Box<&dyn ExperimentData>> {
+        None
+    }
+    /// Called when the dial disconnects or reconnects, so videos can be paused and the outage recorded
+    fn connection_changed(&mut self, _change: ConnectionChange) {}
     fn arc_settings(&self) -> Option<ArcSettings>;
+    /// Whether the participant may navigate back to this screen, or from this screen to an earlier one.
+    /// Screens that record timed decisions (e.g. video trials) must never be revisited.
+    fn allow_back(&self) -> bool {
+        true
+    }
+    /// Whether the screen handles mouse and touch input itself. Elsewhere a click or touch presses the dial button.
+    fn takes_pointer(&self) -> bool {
+        false
+    }
 }
 
-pub mod arc_input_video_view;
-pub mod arc_dichotomous_view;
 pub mod participant_id_view;
 pub mod info_view;
 pub mod multichoice_view;
@@ -50,4 +228,7 @@ pub mod textinput_view;
 pub mod image_view;
 pub mod video_view;
 pub mod lock_in_video_view;
-pub mod arc_question_scale;
\ No newline at end of file
+pub mod continuous_rating_view;
+pub mod recording;
+pub mod mapping;
+pub mod lock_in;
\ No newline at end of file
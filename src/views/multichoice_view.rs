@@ -1,5 +1,7 @@
+use std::time::Instant;
+
 use iced::Alignment;
-use iced::widget::{Button, Column, Row, Radio, Text};
+use iced::widget::{Button, Column, Row, Radio, Space, Text};
 use iced::Element;
 
 use iced::Length;
@@ -13,6 +15,7 @@ use crate::views::DialView;
 
 use super::ExperimentData;
 use super::Printable;
+use crate::data::schema::{EVENT_RESPONSE, EVENT_RESPONSE_LABEL};
 
 struct ChoiceData {
     name: String,
@@ -42,6 +45,13 @@ impl ExperimentData for ChoiceData {
     fn data(&self) -> Box<&dyn super::Printable> {
         Box::new(self)
     }
+
+    fn records(&self) -> Vec<super::DataRecord> {
+        vec![
+            super::DataRecord::metadata(None, EVENT_RESPONSE, self.selection.to_string()),
+            super::DataRecord::metadata(None, EVENT_RESPONSE_LABEL, self.label.clone()),
+        ]
+    }
 }
 
 impl Printable for ChoiceData {
@@ -76,7 +86,7 @@ impl DialView for MultiChoiceView {
         self.data = ChoiceData::new(self.data.name.clone());
     }
 
-    fn update(&mut self, msg: Option<TopLevelEvent>) -> ScreenCommand {
+    fn update(&mut self, msg: Option<TopLevelEvent>, _at: Instant) -> ScreenCommand {
         ScreenCommand::None
     }
 
@@ -106,10 +116,11 @@ impl DialView for MultiChoiceView {
             next_button = next_button.on_press(Message::ButtonPressed);
         }
 
-        column = column.push(Column::new()
-            .align_items(Alignment::End)
+        column = column.push(Row::new()
             .width(Length::Fill)
             .height(Length::Shrink)
+            .push(Button::new(Text::new("Back")).on_press(Message::BackPressed))
+            .push(Space::with_width(Length::Fill))
             .push(next_button)
         );
             
@@ -129,9 +140,7 @@ impl DialView for MultiChoiceView {
     }
 
     fn arc_settings(&self) -> Option<super::ArcSettings> {
-        Some(super::ArcSettings {
-            divisions: 0
-        })
+        Some(super::ArcSettings::detents(0))
     }
 
     fn iced_input(&mut self, msg: Message) -> ScreenCommand {
@@ -151,7 +160,10 @@ impl DialView for MultiChoiceView {
             },
             Message::ButtonPressed => {
                 return ScreenCommand::NextScreen(None);
-            }
+            },
+            Message::BackPressed => {
+                return ScreenCommand::PreviousScreen;
+            },
             _ => { }
         }
 
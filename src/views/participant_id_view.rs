@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::time::Instant;
 
 use iced::Alignment;
 use iced::widget::{Button, Column, Text, Row};
@@ -31,7 +32,7 @@ impl DialView for ParticipantIdView {
         self.text_value = String::default();
     }
 
-    fn update(&mut self, _msg: Option<TopLevelEvent>) -> ScreenCommand {
+    fn update(&mut self, _msg: Option<TopLevelEvent>, _at: Instant) -> ScreenCommand {
         ScreenCommand::None
     }
 
@@ -75,6 +76,10 @@ impl DialView for ParticipantIdView {
         None
     }
 
+    fn allow_back(&self) -> bool {
+        false
+    }
+
     fn iced_input(&mut self, msg: Message) -> ScreenCommand {
         match msg {
             Message::TextInputChanged(s) => {
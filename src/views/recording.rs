@@ -0,0 +1,118 @@
+use std::time::{Duration, Instant};
+
+use yaml_rust::Yaml;
+
+const DEFAULT_SETTLE_WINDOW_MS: u64 = 500;
+
+/// When a screen stores a point of the participant's dynamic decision
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecordingPolicy {
+    /// Once the dial has been still for the window
+    Settle { window: Duration },
+    /// The current value at a fixed interval, whether the dial moved or not
+    FixedRate { interval: Duration },
+    /// Every change of the value
+    EveryChange,
+}
+
+impl Default for RecordingPolicy {
+    fn default() -> RecordingPolicy {
+        RecordingPolicy::Settle { window: Duration::from_millis(DEFAULT_SETTLE_WINDOW_MS) }
+    }
+}
+
+impl RecordingPolicy {
+    /// Reads a screen's `recording` entry, e.g. `{ type: settle, window_ms: 500 }`, `{ type: fixed_rate, interval_ms: 50 }`
+    /// or `{ type: every_change }`. Screens without one use the 500 ms settle window.
+    pub fn from_yaml(yaml: &Yaml) -> RecordingPolicy {
+        if yaml.is_badvalue() {
+            return RecordingPolicy::default();
+        }
+
+        let policy_type = yaml["type"].as_str().expect("The recording policy is missing its type");
+
+        match policy_type {
+            "settle" => RecordingPolicy::Settle {
+                window: Duration::from_millis(yaml["window_ms"].as_i64().map_or(DEFAULT_SETTLE_WINDOW_MS, |w| w as u64)),
+            },
+            "fixed_rate" => {
+                let interval = yaml["interval_ms"].as_i64().expect("The fixed_rate recording policy needs an interval_ms parameter");
+
+                if interval <= 0 {
+                    panic!("The fixed_rate recording interval must be positive");
+                }
+
+                RecordingPolicy::FixedRate { interval: Duration::from_millis(interval as u64) }
+            },
+            "every_change" => RecordingPolicy::EveryChange,
+            _ => panic!("Unknown recording policy '{}' in the protocol", policy_type),
+        }
+    }
+}
+
+/// Decides when points are due under a recording policy. Due points carry the instant they belong to,
+/// so they don't depend on how often the screen happens to be updated.
+pub struct Recorder {
+    policy: RecordingPolicy,
+    // When the value last changed for settle and every change, or the next sample for fixed rate
+    pending: Option<Instant>,
+}
+
+impl Recorder {
+    pub fn new(policy: RecordingPolicy) -> Recorder {
+        Recorder {
+            policy,
+            pending: None,
+        }
+    }
+
+    /// Starts recording, called when the screen is shown
+    pub fn start(&mut self, now: Instant) {
+        self.pending = match self.policy {
+            RecordingPolicy::FixedRate { interval } => Some(now + interval),
+            _ => None,
+        };
+    }
+
+    /// Stops recording once the decision is final
+    pub fn stop(&mut self) {
+        self.pending = None;
+    }
+
+    pub fn changed(&mut self, at: Instant) {
+        match self.policy {
+            RecordingPolicy::Settle { .. } | RecordingPolicy::EveryChange => self.pending = Some(at),
+            RecordingPolicy::FixedRate { .. } => {},
+        }
+    }
+
+    /// Every point due up to `now`, in order. Call this before applying new dial events so each point gets
+    /// the value the dial had at its instant.
+    pub fn due(&mut self, now: Instant) -> Vec<Instant> {
+        let mut points: Vec<Instant> = Vec::new();
+
+        match self.policy {
+            RecordingPolicy::Settle { window } => {
+                if let Some(changed) = self.pending {
+                    if now.duration_since(changed) > window {
+                        points.push(changed + window);
+                        self.pending = None;
+                    }
+                }
+            },
+            RecordingPolicy::FixedRate { interval } => {
+                while let Some(next) = self.pending.filter(|n| *n <= now) {
+                    points.push(next);
+                    self.pending = Some(next + interval);
+                }
+            },
+            RecordingPolicy::EveryChange => {
+                if let Some(changed) = self.pending.take() {
+                    points.push(changed);
+                }
+            },
+        }
+
+        points
+    }
+}
@@ -1,5 +1,7 @@
+use std::time::Instant;
+
 use iced::Alignment;
-use iced::widget::{Button, Column, Row, Text, TextInput};
+use iced::widget::{Button, Column, Row, Space, Text, TextInput};
 use iced::Element;
 
 use iced::Length;
@@ -14,6 +16,7 @@ use crate::views::DialView;
 
 use super::ExperimentData;
 use super::Printable;
+use crate::data::schema::EVENT_RESPONSE;
 
 pub enum TextInputType {
     Alphanumeric,
@@ -48,6 +51,12 @@ impl ExperimentData for TextData {
     fn data(&self) -> Box<&dyn super::Printable> {
         Box::new(self)
     }
+
+    fn records(&self) -> Vec<super::DataRecord> {
+        vec![
+            super::DataRecord::metadata(None, EVENT_RESPONSE, self.text.clone()),
+        ]
+    }
 }
 
 impl Printable for TextData {
@@ -84,7 +93,7 @@ impl DialView for TextInputView {
         self.data = TextData::new(self.data.name.clone());
     }
 
-    fn update(&mut self, _msg: Option<TopLevelEvent>) -> ScreenCommand {
+    fn update(&mut self, _msg: Option<TopLevelEvent>, _at: Instant) -> ScreenCommand {
         ScreenCommand::None
     }
 
@@ -108,10 +117,11 @@ impl DialView for TextInputView {
             next_button = next_button.on_press(Message::ButtonPressed);
         }
 
-        column = column.push(Column::new()
-            .align_items(Alignment::End)
+        column = column.push(Row::new()
             .width(Length::Fill)
             .height(Length::Shrink)
+            .push(Button::new(Text::new("Back")).on_press(Message::BackPressed))
+            .push(Space::with_width(Length::Fill))
             .push(next_button)
         );
 
@@ -163,6 +173,9 @@ impl DialView for TextInputView {
             Message::ButtonPressed => {
                 return ScreenCommand::NextScreen(None);
             },
+            Message::BackPressed => {
+                return ScreenCommand::PreviousScreen;
+            },
             _ => {
 
             }
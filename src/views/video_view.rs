@@ -13,6 +13,7 @@ use url::Url;
 use surface_dial_rs::events::{DialEvent, TopLevelEvent};
 
 use crate::Message;
+use crate::connection::ConnectionChange;
 use crate::arc_input::ArcInput;
 
 use crate::views::ScreenCommand;
@@ -38,7 +39,7 @@ impl DialView for VideoView {
 
     }
 
-    fn update(&mut self, msg: Option<TopLevelEvent>) -> ScreenCommand {
+    fn update(&mut self, msg: Option<TopLevelEvent>, _at: Instant) -> ScreenCommand {
         match msg {
             Some(e) => {
 
@@ -49,10 +50,6 @@ impl DialView for VideoView {
                         }
                     }
                 }
-
-                if let TopLevelEvent::ConnectionEvent(c) = e {
-                    println!("Connection event: {:?}", c);
-                }
             },
             _ => {}
         }
@@ -104,10 +101,24 @@ impl DialView for VideoView {
         None
     }
 
+    fn connection_changed(&mut self, change: ConnectionChange) {
+        // Nothing should play while the participant can't respond
+        if let Some(v) = self.video.as_mut() {
+            match change {
+                ConnectionChange::Lost { .. } => v.set_paused(true),
+                ConnectionChange::Restored { .. } => v.set_paused(self.finished),
+            }
+        }
+    }
+
     fn arc_settings(&self) -> Option<super::ArcSettings> {
         None
     }
 
+    fn allow_back(&self) -> bool {
+        false
+    }
+
     fn iced_input(&mut self, msg: Message) -> ScreenCommand {
         ScreenCommand::None
     }